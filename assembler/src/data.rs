@@ -0,0 +1,42 @@
+use edu_asm_parser::directive::{byte_value, Directive};
+
+use crate::immediate::encode_immediate;
+
+/// Encodes the bytes `directive` emits into the data section, when placed at `address`
+pub fn encode_directive(directive: &Directive, address: u64) -> Vec<u8> {
+    match directive {
+        Directive::Section(_) | Directive::Global(_) | Directive::Extern(_) => Vec::new(),
+        Directive::Word(values) => values.iter().flat_map(encode_immediate).collect(),
+        Directive::Byte(values) => values
+            .iter()
+            .map(|v| byte_value(v).expect("the parser checks the range of bytes"))
+            .collect(),
+        Directive::Ascii(bytes) => bytes.clone(),
+        Directive::Asciz(bytes) => {
+            let mut ret = bytes.clone();
+            ret.push(0);
+            ret
+        }
+        Directive::Zero(_) | Directive::Align(_) => {
+            vec![0; directive.size_at(address) as usize]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn encode() {
+        let encode = |s: &str, address| encode_directive(&Directive::from_str(s).unwrap(), address);
+
+        assert_eq!(encode(".byte -1 2", 0), [255, 2]);
+        assert_eq!(encode(".word 258", 0), [2, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(encode(".asciz \"hi\"", 0), [b'h', b'i', 0]);
+        assert_eq!(encode(".align 4", 5), [0, 0, 0]);
+        assert_eq!(encode(".zero 2", 5), [0, 0]);
+    }
+}
@@ -0,0 +1,548 @@
+use std::rc::Rc;
+
+use edu_asm_parser::{
+    instruction::{
+        ArithmeticBase, ArithmeticBitLogic, ArithmeticMultDivEasy, ArithmeticShift, ControlFlow,
+        Instruction, Memory, Misc, RegisterOrLiteral,
+    },
+    label::LocAwLabel,
+    label_ref::LabelRefToken,
+    literal::LiteralToken,
+    register::RegisterToken,
+};
+use thiserror::Error;
+
+use crate::{
+    instruction_ident::{inst::*, *},
+    mode::{OperationMode, RegisterLiteral},
+    register::decode_register_token,
+};
+
+/// Maximum size of an encoded instruction in bytes (ident, mode and eight immediates)
+pub const MAX_INSTRUCTION_SIZE: usize = 3 + 1 + 8 * 8;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The offset of the read that failed, relative to the start of the instruction
+    #[error("bytecode ended unexpectedly at byte `{0}` of the instruction")]
+    UnexpectedEnd(usize),
+    #[error("instruction ident `{0:?}` is unknown")]
+    UnknownInstruction([u8; 3]),
+    #[error("register code `{0}` is invalid")]
+    InvalidRegister(u8),
+    #[error("operand `{0}` has to be a register")]
+    ExpectedRegister(usize),
+    #[error("operand `{0}` has to be an immediate")]
+    ExpectedImmediate(usize),
+}
+
+struct Reader<'a> {
+    inp: &'a [u8],
+    pos: usize,
+    mode: OperationMode,
+    operand: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(inp: &'a [u8]) -> Self {
+        Self {
+            inp,
+            pos: 0,
+            mode: OperationMode::new(&[]),
+            operand: 0,
+        }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let end = self.pos + N;
+        let bytes = self
+            .inp
+            .get(self.pos..end)
+            .ok_or(DecodeError::UnexpectedEnd(self.pos))?;
+        self.pos = end;
+        let mut ret = [0u8; N];
+        ret.copy_from_slice(bytes);
+        Ok(ret)
+    }
+
+    fn mode(&mut self) -> Result<(), DecodeError> {
+        let [mode] = self.take::<1>()?;
+        self.mode = OperationMode::decode(mode);
+        Ok(())
+    }
+
+    fn operand(&mut self) -> Result<RegisterOrLiteral, DecodeError> {
+        let index = self.operand;
+        self.operand += 1;
+        match self.mode.get(index) {
+            RegisterLiteral::Register => {
+                let [code] = self.take::<1>()?;
+                decode_register_token(code)
+                    .map(RegisterOrLiteral::Register)
+                    .ok_or(DecodeError::InvalidRegister(code))
+            }
+            RegisterLiteral::Literal => {
+                let bytes = self.take::<8>()?;
+                let value = u64::from_le_bytes(bytes);
+                Ok(RegisterOrLiteral::Literal(LiteralToken::Unsigned(value)))
+            }
+        }
+    }
+
+    /// Reads an operand of a signed instruction, immediates are decoded as signed literals
+    fn signed(&mut self) -> Result<RegisterOrLiteral, DecodeError> {
+        match self.operand()? {
+            RegisterOrLiteral::Literal(LiteralToken::Unsigned(value)) => Ok(
+                RegisterOrLiteral::Literal(LiteralToken::Signed(value as i64)),
+            ),
+            d => Ok(d),
+        }
+    }
+
+    fn register(&mut self) -> Result<RegisterToken, DecodeError> {
+        let index = self.operand;
+        match self.operand()? {
+            RegisterOrLiteral::Register(r) => Ok(r),
+            RegisterOrLiteral::Literal(_) => Err(DecodeError::ExpectedRegister(index)),
+        }
+    }
+
+    fn label(&mut self) -> Result<LabelRefToken, DecodeError> {
+        let index = self.operand;
+        match self.operand()? {
+            RegisterOrLiteral::Literal(LiteralToken::Unsigned(loc)) => Ok(decode_label_ref(loc)),
+            _ => Err(DecodeError::ExpectedImmediate(index)),
+        }
+    }
+}
+
+/// Creates an unnamed label reference pointing to the byte offset `loc`
+pub fn decode_label_ref(loc: u64) -> LabelRefToken {
+    let loc = usize::try_from(loc).unwrap_or(usize::MAX);
+    LabelRefToken {
+        content: String::new(),
+        label: Some(Rc::new(LocAwLabel {
+            name: String::new(),
+            loc,
+        })),
+    }
+}
+
+fn decode_arithmetic_base(
+    ident: [u8; 3],
+    reader: &mut Reader,
+) -> Result<ArithmeticBase, DecodeError> {
+    reader.mode()?;
+    let ret = match ident {
+        ADD_T_S_INST => ArithmeticBase::AddTs {
+            d: reader.register()?,
+            s: reader.signed()?,
+            t: reader.signed()?,
+        },
+        ADD_I_S_INST => ArithmeticBase::AddIs {
+            s: reader.register()?,
+            t: reader.signed()?,
+        },
+        ADD_T_U_INST => ArithmeticBase::AddTu {
+            d: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        ADD_I_U_INST => ArithmeticBase::AddIu {
+            s: reader.register()?,
+            t: reader.operand()?,
+        },
+        SUB_T_S_INST => ArithmeticBase::SubTs {
+            d: reader.register()?,
+            s: reader.signed()?,
+            t: reader.signed()?,
+        },
+        SUB_I_S_INST => ArithmeticBase::SubIs {
+            s: reader.register()?,
+            t: reader.signed()?,
+        },
+        SUB_T_U_INST => ArithmeticBase::SubTu {
+            d: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        SUB_I_U_INST => ArithmeticBase::SubIu {
+            s: reader.register()?,
+            t: reader.operand()?,
+        },
+        _ => return Err(DecodeError::UnknownInstruction(ident)),
+    };
+    Ok(ret)
+}
+
+fn decode_arithmetic_shift(
+    ident: [u8; 3],
+    reader: &mut Reader,
+) -> Result<ArithmeticShift, DecodeError> {
+    reader.mode()?;
+    let ret = match ident {
+        LSHL_T_S_INST => ArithmeticShift::LshLT {
+            d: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        LSHL_I_S_INST => ArithmeticShift::LshLI {
+            s: reader.register()?,
+            t: reader.operand()?,
+        },
+        LSHR_T_S_INST => ArithmeticShift::LshRT {
+            d: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        LSHR_I_S_INST => ArithmeticShift::LshRI {
+            s: reader.register()?,
+            t: reader.operand()?,
+        },
+        ASHR_T_S_INST => ArithmeticShift::AshRT {
+            d: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        ASHR_I_S_INST => ArithmeticShift::AshRI {
+            s: reader.register()?,
+            t: reader.operand()?,
+        },
+        _ => return Err(DecodeError::UnknownInstruction(ident)),
+    };
+    Ok(ret)
+}
+
+fn decode_arithmetic_bit_log(
+    ident: [u8; 3],
+    reader: &mut Reader,
+) -> Result<ArithmeticBitLogic, DecodeError> {
+    reader.mode()?;
+    let ret = match ident {
+        AND_T_S_INST => ArithmeticBitLogic::AndT {
+            d: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        AND_I_S_INST => ArithmeticBitLogic::AndI {
+            s: reader.register()?,
+            t: reader.operand()?,
+        },
+        OR_T_S_INST => ArithmeticBitLogic::OrT {
+            d: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        OR_I_S_INST => ArithmeticBitLogic::OrI {
+            s: reader.register()?,
+            t: reader.operand()?,
+        },
+        XOR_T_S_INST => ArithmeticBitLogic::XorT {
+            d: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        XOR_I_S_INST => ArithmeticBitLogic::XorI {
+            s: reader.register()?,
+            t: reader.operand()?,
+        },
+        NOT_T_S_INST => ArithmeticBitLogic::NotT {
+            d: reader.register()?,
+            s: reader.operand()?,
+        },
+        NOT_I_S_INST => ArithmeticBitLogic::NoI {
+            s: reader.register()?,
+        },
+        _ => return Err(DecodeError::UnknownInstruction(ident)),
+    };
+    Ok(ret)
+}
+
+fn decode_arithmetic_mult_div(
+    ident: [u8; 3],
+    reader: &mut Reader,
+) -> Result<ArithmeticMultDivEasy, DecodeError> {
+    reader.mode()?;
+    let ret = match ident {
+        MUL_T_S_E_INST => ArithmeticMultDivEasy::MulTsE {
+            d: reader.register()?,
+            s: reader.signed()?,
+            t: reader.signed()?,
+        },
+        MUL_I_S_E_INST => ArithmeticMultDivEasy::MulIsE {
+            s: reader.register()?,
+            t: reader.signed()?,
+        },
+        MUL_T_U_E_INST => ArithmeticMultDivEasy::MulTuE {
+            d: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        MUL_I_U_E_INST => ArithmeticMultDivEasy::MulIuE {
+            s: reader.register()?,
+            t: reader.operand()?,
+        },
+        DIV_T_S_E_INST => ArithmeticMultDivEasy::DivTsE {
+            d: reader.register()?,
+            r: reader.register()?,
+            s: reader.signed()?,
+            t: reader.signed()?,
+        },
+        DIV_T_U_E_INST => ArithmeticMultDivEasy::DivTuE {
+            d: reader.register()?,
+            r: reader.register()?,
+            s: reader.operand()?,
+            t: reader.operand()?,
+        },
+        _ => return Err(DecodeError::UnknownInstruction(ident)),
+    };
+    Ok(ret)
+}
+
+fn decode_control_flow(ident: [u8; 3], reader: &mut Reader) -> Result<ControlFlow, DecodeError> {
+    reader.mode()?;
+    let ret = match ident {
+        JMP_INST => ControlFlow::Jmp {
+            label: reader.label()?,
+        },
+        JMP_EQ_INST => ControlFlow::JmpEq {
+            l: reader.operand()?,
+            r: reader.operand()?,
+            label: reader.label()?,
+        },
+        JMP_NE_INST => ControlFlow::JmpNe {
+            l: reader.operand()?,
+            r: reader.operand()?,
+            label: reader.label()?,
+        },
+        JMP_GT_S_INST => ControlFlow::JmpGtS {
+            l: reader.signed()?,
+            r: reader.signed()?,
+            label: reader.label()?,
+        },
+        JMP_GE_S_INST => ControlFlow::JmpGeS {
+            l: reader.signed()?,
+            r: reader.signed()?,
+            label: reader.label()?,
+        },
+        JMP_LT_S_INST => ControlFlow::JmpLtS {
+            l: reader.signed()?,
+            r: reader.signed()?,
+            label: reader.label()?,
+        },
+        JMP_LE_S_INST => ControlFlow::JmpLeS {
+            l: reader.signed()?,
+            r: reader.signed()?,
+            label: reader.label()?,
+        },
+        JMP_GT_U_INST => ControlFlow::JmpGtU {
+            l: reader.operand()?,
+            r: reader.operand()?,
+            label: reader.label()?,
+        },
+        JMP_GE_U_INST => ControlFlow::JmpGeU {
+            l: reader.operand()?,
+            r: reader.operand()?,
+            label: reader.label()?,
+        },
+        JMP_LT_U_INST => ControlFlow::JmpLtU {
+            l: reader.operand()?,
+            r: reader.operand()?,
+            label: reader.label()?,
+        },
+        JMP_LE_U_INST => ControlFlow::JmpLeU {
+            l: reader.operand()?,
+            r: reader.operand()?,
+            label: reader.label()?,
+        },
+        JMP_R_INST => ControlFlow::JmpR {
+            s: reader.register()?,
+        },
+        CAL_INST => ControlFlow::Cal {
+            label: reader.label()?,
+        },
+        CAL_R_INST => ControlFlow::CalR {
+            s: reader.register()?,
+        },
+        RET_INST => ControlFlow::Ret {
+            s: reader.register()?,
+        },
+        _ => return Err(DecodeError::UnknownInstruction(ident)),
+    };
+    Ok(ret)
+}
+
+fn decode_memory(ident: [u8; 3], reader: &mut Reader) -> Result<Memory, DecodeError> {
+    reader.mode()?;
+    let ret = match ident {
+        MOV_INST => Memory::Mov {
+            t: reader.register()?,
+            s: reader.operand()?,
+        },
+        MOV_L_INST => Memory::MovL {
+            t: reader.register()?,
+            label: reader.label()?,
+        },
+        LOAD_INST => Memory::Load {
+            t: reader.register()?,
+            s: reader.operand()?,
+        },
+        LOAD_O_INST => Memory::LoadO {
+            t: reader.register()?,
+            s: reader.operand()?,
+            o: reader.operand()?,
+        },
+        LOAD_B_INST => Memory::Loadb {
+            t: reader.register()?,
+            s: reader.operand()?,
+        },
+        LOAD_B_O_INST => Memory::LoadbO {
+            t: reader.register()?,
+            s: reader.operand()?,
+            o: reader.operand()?,
+        },
+        // the store instructions are encoded with the target address first
+        STOR_INST => {
+            let t = reader.operand()?;
+            let s = reader.operand()?;
+            Memory::Stor { s, t }
+        }
+        STOR_O_INST => {
+            let t = reader.operand()?;
+            let s = reader.operand()?;
+            let o = reader.operand()?;
+            Memory::StorO { s, t, o }
+        }
+        STOR_B_INST => {
+            let t = reader.operand()?;
+            let s = reader.operand()?;
+            Memory::Storb { s, t }
+        }
+        STOR_B_O_INST => {
+            let t = reader.operand()?;
+            let s = reader.operand()?;
+            let o = reader.operand()?;
+            Memory::StorbO { s, t, o }
+        }
+        PUSH_INST => Memory::Push {
+            d: reader.register()?,
+        },
+        POP_INST => Memory::Pop {
+            d: reader.register()?,
+        },
+        _ => return Err(DecodeError::UnknownInstruction(ident)),
+    };
+    Ok(ret)
+}
+
+fn decode_misc(ident: [u8; 3], reader: &mut Reader) -> Result<Misc, DecodeError> {
+    let ret = match ident {
+        HALT_INST => Misc::Halt,
+        EXIT_INST => {
+            reader.mode()?;
+            Misc::Exit {
+                s: reader.signed()?,
+            }
+        }
+        PRINT_INST => {
+            reader.mode()?;
+            Misc::Print {
+                s: reader.operand()?,
+            }
+        }
+        READ_INST => {
+            reader.mode()?;
+            Misc::Read {
+                s: reader.register()?,
+            }
+        }
+        DUMP_INST => Misc::Dump,
+        NOP_INST => Misc::Nop,
+        _ => return Err(DecodeError::UnknownInstruction(ident)),
+    };
+    Ok(ret)
+}
+
+/// Decodes the instruction at the start of `inp`.
+///
+/// Returns the instruction and the number of bytes it occupies. Jump and call targets are
+/// returned as unnamed label references, carrying the byte offset of the target.
+pub fn decode_instruction(inp: &[u8]) -> Result<(Instruction, usize), DecodeError> {
+    let mut reader = Reader::new(inp);
+    let ident = reader.take::<3>()?;
+    let instruction = match InstructionIdent::decode(ident).group {
+        ARITHMETIC_BASE_GROUP => {
+            Instruction::ArithmeticBase(decode_arithmetic_base(ident, &mut reader)?)
+        }
+        ARITHMETIC_SHIFT_GROUP => {
+            Instruction::ArithmeticShift(decode_arithmetic_shift(ident, &mut reader)?)
+        }
+        ARITHMETIC_BIT_LOG_GROUP => {
+            Instruction::ArithmeticBitLogic(decode_arithmetic_bit_log(ident, &mut reader)?)
+        }
+        ARITHMETIC_MULT_DIV_EASY_GROUP => {
+            Instruction::ArithmeticMultDivEasy(decode_arithmetic_mult_div(ident, &mut reader)?)
+        }
+        JUMP_GROUP | FUNCTION_GROUP => {
+            Instruction::ControlFlow(decode_control_flow(ident, &mut reader)?)
+        }
+        MEMORY_GROUP | STACK_GROUP => Instruction::Memory(decode_memory(ident, &mut reader)?),
+        MISC_GROUP => Instruction::Misc(decode_misc(ident, &mut reader)?),
+        _ => return Err(DecodeError::UnknownInstruction(ident)),
+    };
+    Ok((instruction, reader.pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use edu_asm_parser::parse;
+
+    use crate::{assemble, instruction::encode_instruction};
+
+    use super::*;
+
+    #[test]
+    fn decode_assembled_program() {
+        const PROGRAM: &str = r#"
+        _start:
+            addts $G_0 $Z -10
+            loadbo $G_1 $G_0 3u
+            storb $G_1 $S_E
+            push $G_1
+            pop $G_2
+            jmpne $G_0 $G_2 :_start
+            dump
+            exit $G_0
+        "#;
+
+        let (parsed, _) = parse(PROGRAM).unwrap();
+        let assembled = assemble(parsed).unwrap().code;
+
+        let mut pos = 0;
+        let mut reassembled = Vec::new();
+        while pos < assembled.len() {
+            let (instruction, len) = decode_instruction(&assembled[pos..]).unwrap();
+            reassembled.extend(encode_instruction(instruction));
+            pos += len;
+        }
+
+        assert_eq!(assembled, reassembled);
+    }
+
+    #[test]
+    fn decode_truncated() {
+        let encoded = encode_instruction(Instruction::Misc(Misc::Exit {
+            s: RegisterOrLiteral::Literal(LiteralToken::Unsigned(1)),
+        }));
+
+        // the immediate starts after the ident and the mode
+        assert_eq!(
+            decode_instruction(&encoded[..encoded.len() - 1]),
+            Err(DecodeError::UnexpectedEnd(4))
+        );
+        assert_eq!(
+            decode_instruction(&encoded[..2]),
+            Err(DecodeError::UnexpectedEnd(0))
+        );
+    }
+}
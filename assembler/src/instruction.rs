@@ -92,9 +92,16 @@ pub fn build_complete_instruction(instruction_ident: [u8; 3], parameters: Vec<u8
     ret
 }
 
+/// Encodes the location of the label.
+///
+/// Panics if the reference isn't resolved, `assemble_object` resolves references to labels of
+/// other files to a placeholder until linking.
 #[inline(always)]
 pub fn encode_label_ref(label_ref: LabelRefToken) -> RegisterOrLiteral {
-    let loc_usize = label_ref.label.unwrap().loc;
+    let loc_usize = match label_ref.label {
+        Some(label) => label.loc,
+        None => panic!("label `{}` is not resolved", label_ref.content),
+    };
     let loc = u64::try_from(loc_usize).unwrap();
     RegisterOrLiteral::Literal(LiteralToken::Unsigned(loc))
 }
@@ -244,6 +251,12 @@ pub fn encode_control_flow(instruction: ControlFlow) -> Vec<u8> {
         ControlFlow::JmpLeU { l, r, label } => {
             build_complete_instruction(JMP_LE_U_INST, encode_three(l, r, encode_label_ref(label)))
         }
+        ControlFlow::JmpR { s } => {
+            build_complete_instruction(JMP_R_INST, encode_instruction_parameter(&[s.into()]))
+        }
+        ControlFlow::CalR { s } => {
+            build_complete_instruction(CAL_R_INST, encode_instruction_parameter(&[s.into()]))
+        }
         ControlFlow::Cal { label } => build_complete_instruction(
             CAL_INST,
             encode_instruction_parameter(&[encode_label_ref(label)]),
@@ -258,6 +271,9 @@ pub fn encode_control_flow(instruction: ControlFlow) -> Vec<u8> {
 pub fn encode_memory(instruction: Memory) -> Vec<u8> {
     match instruction {
         Memory::Mov { t, s } => build_complete_instruction(MOV_INST, encode_two(t, s)),
+        Memory::MovL { t, label } => {
+            build_complete_instruction(MOV_L_INST, encode_two(t, encode_label_ref(label)))
+        }
         Memory::Load { t, s } => build_complete_instruction(LOAD_INST, encode_two(t, s)),
         Memory::LoadO { t, s, o } => build_complete_instruction(LOAD_O_INST, encode_three(t, s, o)),
         Memory::Loadb { t, s } => build_complete_instruction(LOAD_B_INST, encode_two(t, s)),
@@ -297,6 +313,7 @@ pub fn encode_misc(insruction: Misc) -> Vec<u8> {
     }
 }
 
+/// Encodes `instruction` into bytecode, panics if its label reference isn't resolved
 #[inline]
 pub fn encode_instruction(instruction: Instruction) -> Vec<u8> {
     match instruction {
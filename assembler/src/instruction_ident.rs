@@ -166,6 +166,8 @@ pub const JMP_GE_INST_CODE: u16 = 4;
 pub const JMP_LT_INST_CODE: u16 = 5;
 /// Instruction code for the `jmple` instructions
 pub const JMP_LE_INST_CODE: u16 = 6;
+/// Instruction code for the `jmpr` instruction
+pub const JMP_R_INST_CODE: u16 = 7;
 
 // Function instructions
 
@@ -173,6 +175,8 @@ pub const JMP_LE_INST_CODE: u16 = 6;
 pub const CALL_INST_CODE: u16 = 0;
 /// Instruction code for the `ret` instruction
 pub const RET_INST_CODE: u16 = 1;
+/// Instruction code for the `calr` instruction
+pub const CALL_R_INST_CODE: u16 = 2;
 
 // Memory move instructions
 
@@ -186,6 +190,8 @@ pub const LOAD_O_INST_CODE: u16 = 2;
 pub const STORE_INST_CODE: u16 = 3;
 /// Instruction code for the `storeo` instructions
 pub const STORE_O_INST_CODE: u16 = 4;
+/// Instruction code for the `mov` instruction with a label as source
+pub const MOV_L_INST_CODE: u16 = 5;
 
 // Stack instructions
 
@@ -348,14 +354,21 @@ pub mod inst {
     /// Instruction ident for the `jmpleu` instruction
     pub const JMP_LE_U_INST: [u8; 3] =
         encode_instruction(JMP_LE_INST_CODE, UNSIGNED_TYPE_INFO, JUMP_GROUP);
+    /// Instruction ident for the `jmpr` instruction
+    pub const JMP_R_INST: [u8; 3] = encode_instruction(JMP_R_INST_CODE, UNTYPED_TYPE, JUMP_GROUP);
 
     /// Instruction ident for the `cal` instruction
     pub const CAL_INST: [u8; 3] = encode_instruction(CALL_INST_CODE, UNTYPED_TYPE, FUNCTION_GROUP);
     /// Instruction ident for the `ret` instruction
     pub const RET_INST: [u8; 3] = encode_instruction(RET_INST_CODE, UNTYPED_TYPE, FUNCTION_GROUP);
+    /// Instruction ident for the `calr` instruction
+    pub const CAL_R_INST: [u8; 3] =
+        encode_instruction(CALL_R_INST_CODE, UNTYPED_TYPE, FUNCTION_GROUP);
 
     /// Instruction ident for the `mov` instruction
     pub const MOV_INST: [u8; 3] = encode_instruction(MOV_INST_CODE, UNTYPED_TYPE, MEMORY_GROUP);
+    /// Instruction ident for the `mov` instruction with a label as source
+    pub const MOV_L_INST: [u8; 3] = encode_instruction(MOV_L_INST_CODE, UNTYPED_TYPE, MEMORY_GROUP);
     /// Instruction ident for the `load` instruction
     pub const LOAD_INST: [u8; 3] = encode_instruction(LOAD_INST_CODE, WORD_TYPE_INFO, MEMORY_GROUP);
     /// Instruction ident for the `loado` instruction
@@ -363,7 +376,7 @@ pub mod inst {
         encode_instruction(LOAD_O_INST_CODE, WORD_TYPE_INFO, MEMORY_GROUP);
     /// Instruction ident for the `loadb` instruction
     pub const LOAD_B_INST: [u8; 3] =
-        encode_instruction(LOAD_O_INST_CODE, BYTE_TYPE_INFO, MEMORY_GROUP);
+        encode_instruction(LOAD_INST_CODE, BYTE_TYPE_INFO, MEMORY_GROUP);
     /// Instruction ident for the `loadbo` instruction
     pub const LOAD_B_O_INST: [u8; 3] =
         encode_instruction(LOAD_O_INST_CODE, BYTE_TYPE_INFO, MEMORY_GROUP);
@@ -375,15 +388,15 @@ pub mod inst {
         encode_instruction(STORE_O_INST_CODE, WORD_TYPE_INFO, MEMORY_GROUP);
     /// Instruction ident for the `storb` instruction
     pub const STOR_B_INST: [u8; 3] =
-        encode_instruction(STORE_O_INST_CODE, BYTE_TYPE_INFO, MEMORY_GROUP);
+        encode_instruction(STORE_INST_CODE, BYTE_TYPE_INFO, MEMORY_GROUP);
     /// Instruction ident for the `storbo` instruction
     pub const STOR_B_O_INST: [u8; 3] =
         encode_instruction(STORE_O_INST_CODE, BYTE_TYPE_INFO, MEMORY_GROUP);
 
     /// Instruction ident for the `push` instruction
-    pub const PUSH_INST: [u8; 3] = encode_instruction(PUSH_INST_CODE, WORD_TYPE_INFO, MEMORY_GROUP);
+    pub const PUSH_INST: [u8; 3] = encode_instruction(PUSH_INST_CODE, WORD_TYPE_INFO, STACK_GROUP);
     /// Instruction ident for the `pop` instruction
-    pub const POP_INST: [u8; 3] = encode_instruction(POP_INST_CODE, WORD_TYPE_INFO, MEMORY_GROUP);
+    pub const POP_INST: [u8; 3] = encode_instruction(POP_INST_CODE, WORD_TYPE_INFO, STACK_GROUP);
 
     /// Instruction ident for the `halt` instruction
     pub const HALT_INST: [u8; 3] = encode_instruction(HALT_INST_CODE, UNTYPED_TYPE, MISC_GROUP);
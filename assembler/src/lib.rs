@@ -1,13 +1,42 @@
+pub mod data;
+pub mod decode;
 pub mod immediate;
 pub mod instruction;
 pub mod instruction_ident;
+pub mod link;
 pub mod mode;
+pub mod object;
+pub mod program;
 pub mod register;
 
 use std::{collections::HashMap, rc::Rc};
 
-use edu_asm_parser::{instruction::Instruction, label::LocAwLabel, PureElement};
+use data::encode_directive;
+pub use edu_asm_parser::DEFAULT_ENTRY;
+use edu_asm_parser::{
+    directive::{Directive, Section},
+    label::LocAwLabel,
+    PureElement,
+};
 use instruction::encode_instruction;
+use link::link;
+use object::{Object, ObjectSymbol, Relocation};
+use program::{DebugLine, Program};
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum AssembleError {
+    #[error("the entry point `{0}` is not defined, execution starts at this label")]
+    MissingEntry(String),
+    #[error("the entry point `{0}` is not a label in the text section")]
+    EntryOutsideText(String),
+    /// A global label defined by two objects, given by their index
+    #[error("label `{0}` is defined more than once")]
+    DuplicateSymbol(String, usize, usize),
+    /// A label, that isn't defined by any object, referenced by the object at the index
+    #[error("label `{}` is used at `{}:{}`, but never defined", .0.symbol, .0.line + 1, .0.column + 1)]
+    UndefinedSymbol(Relocation, usize),
+}
 
 #[inline]
 pub fn update_pure_elements(
@@ -16,51 +45,157 @@ pub fn update_pure_elements(
 ) -> Vec<PureElement> {
     elements
         .iter()
-        .filter(|e| !matches!(e, PureElement::Label(_)))
+        .filter(|e| !matches!(e, PureElement::Label(..)))
         .map(|e| match e {
-            PureElement::Instruction(Instruction::ControlFlow(c)) => {
-                let mut locale = c.clone();
-                match locale.get_label() {
-                    Some(d) => {
-                        let loc_label = map.get(&d.content).cloned().unwrap();
+            PureElement::Instruction(i, spans) => {
+                let mut locale = i.clone();
+                if let Some(d) = locale.get_label() {
+                    let name = d.label.as_ref().map_or(&d.content, |l| &l.name);
+                    // labels of other files are filled in when linking
+                    if let Some(loc_label) = map.get(name).cloned() {
                         locale.hydrate(loc_label);
-                        PureElement::Instruction(Instruction::ControlFlow(locale))
                     }
-                    None => PureElement::Instruction(Instruction::ControlFlow(locale)),
                 }
+                PureElement::Instruction(locale, spans.clone())
             }
             _ => e.clone(),
         })
         .collect()
 }
 
-pub fn assemble(elements: Vec<PureElement>) -> Vec<u8> {
+/// Resolves the references to labels of other files to a placeholder at zero, which [`link`]
+/// replaces through the relocations
+fn resolve_externs(elements: Vec<PureElement>) -> Vec<PureElement> {
+    elements
+        .into_iter()
+        .map(|e| match e {
+            PureElement::Instruction(mut i, spans) => {
+                if let Some(label_ref) = i.get_label_mut().filter(|l| l.label.is_none()) {
+                    label_ref.label = Some(Rc::new(LocAwLabel {
+                        name: label_ref.content.clone(),
+                        loc: 0,
+                    }));
+                }
+                PureElement::Instruction(i, spans)
+            }
+            e => e,
+        })
+        .collect()
+}
+
+/// Assembles `elements` into a program starting at [`DEFAULT_ENTRY`], see [`assemble_program`]
+pub fn assemble(elements: Vec<PureElement>) -> Result<Program, AssembleError> {
+    assemble_program(elements, DEFAULT_ENTRY)
+}
+
+/// Assembles `elements` into separate code and data sections, starting at the label `entry`.
+///
+/// Labels in the text section resolve to the byte offset of the following instruction, labels in
+/// the data section to the address of the following data, which starts right after the code.
+pub fn assemble_program(elements: Vec<PureElement>, entry: &str) -> Result<Program, AssembleError> {
+    link(&[assemble_object(elements)], entry)
+}
+
+/// Assembles `elements` into an object, which can be linked with others by [`link`].
+///
+/// Every label reference is recorded as a relocation, references to `.extern` labels are left
+/// zero until linking.
+pub fn assemble_object(elements: Vec<PureElement>) -> Object {
+    let elements = resolve_externs(elements);
+    let code_size: usize = elements
+        .iter()
+        .map(|e| match e {
+            PureElement::Instruction(i, _) => encode_instruction(i.clone()).len(),
+            _ => 0,
+        })
+        .sum();
+
     let mut byte_counter = 0;
+    let mut data_counter = code_size as u64;
+    let mut section = Section::Text;
     let mut label_maps: HashMap<String, Rc<LocAwLabel>> = HashMap::new();
+    let mut ret = Object {
+        code: Vec::with_capacity(code_size),
+        data: Vec::new(),
+        data_align: 1,
+        symbols: Vec::new(),
+        relocations: Vec::new(),
+        debug_lines: Vec::new(),
+    };
     for element in elements.iter() {
         match element {
-            PureElement::Label(l) => {
+            PureElement::Label(l, _) => {
+                let loc = match section {
+                    Section::Text => byte_counter,
+                    Section::Data => data_counter as usize,
+                };
                 let label = Rc::new(LocAwLabel {
                     name: l.name.clone(),
-                    loc: byte_counter,
+                    loc,
                 });
                 label_maps.insert(l.name.clone(), label);
+                ret.symbols.push(ObjectSymbol {
+                    name: l.name.clone(),
+                    section,
+                    offset: match section {
+                        Section::Text => loc as u64,
+                        Section::Data => data_counter - code_size as u64,
+                    },
+                    global: false,
+                });
             }
-            PureElement::Instruction(i) => {
+            PureElement::Instruction(i, _) => {
                 let instruction_bytes = encode_instruction(i.clone());
                 byte_counter += instruction_bytes.len();
             }
+            PureElement::Directive(Directive::Section(s), _) => section = *s,
+            PureElement::Directive(d, _) => {
+                if let Directive::Align(alignment) = d {
+                    ret.data_align = ret.data_align.max(*alignment);
+                }
+                data_counter += d.size_at(data_counter);
+            }
+        }
+    }
+    for element in elements.iter() {
+        if let PureElement::Directive(Directive::Global(name), _) = element {
+            ret.symbols
+                .iter_mut()
+                .filter(|s| &s.name == name)
+                .for_each(|s| s.global = true);
         }
     }
+
     let elements = update_pure_elements(label_maps, elements);
-    let mut ret = Vec::with_capacity(byte_counter);
-    elements
-        .iter()
-        .map(|e| match e {
-            PureElement::Instruction(i) => encode_instruction(i.clone()),
-            _ => vec![],
-        })
-        .for_each(|mut e| ret.append(&mut e));
+    for element in elements.iter() {
+        match element {
+            PureElement::Instruction(i, spans) => {
+                ret.debug_lines.push(DebugLine {
+                    offset: ret.code.len() as u64,
+                    file: 0,
+                    line: spans.instruction.line as u64,
+                    column: spans.instruction.column as u64,
+                    operands: spans.operands.iter().map(|s| s.column as u64).collect(),
+                });
+                ret.code.append(&mut encode_instruction(i.clone()));
+                if let Some(label_ref) = i.get_label() {
+                    // the label is always the last operand, encoded as an 8-byte immediate
+                    let span = spans.operands.last().unwrap_or(&spans.instruction);
+                    ret.relocations.push(Relocation {
+                        offset: ret.code.len() as u64 - 8,
+                        symbol: label_ref.label.as_ref().unwrap().name.clone(),
+                        line: span.line as u64,
+                        column: span.column as u64,
+                    });
+                }
+            }
+            PureElement::Directive(d, _) => {
+                let address = (code_size + ret.data.len()) as u64;
+                ret.data.append(&mut encode_directive(d, address));
+            }
+            PureElement::Label(..) => {}
+        }
+    }
     ret
 }
 
@@ -68,14 +203,69 @@ pub fn assemble(elements: Vec<PureElement>) -> Vec<u8> {
 mod tests {
     use edu_asm_parser::parse;
 
-    use crate::assemble;
+    use crate::{assemble, assemble_program, AssembleError, DEFAULT_ENTRY};
 
     #[test]
     fn it_works() {
         let program = include_str!("../../examples/basic.edu");
         let (parsed, _) = parse(program).unwrap();
         println!("{:?}", parsed);
-        let assembled = assemble(parsed);
+        let assembled = assemble(parsed).unwrap();
         println!("{:?}", assembled);
     }
+
+    #[test]
+    fn data_labels() {
+        let program = "_start:
+            mov $G_0 :values
+            exit 0
+        .data
+            .byte 1
+        values:
+            .align 8
+            .word 2
+        ";
+        let (parsed, _) = parse(program).unwrap();
+
+        let assembled = assemble_program(parsed, DEFAULT_ENTRY).unwrap();
+
+        // `mov` with an immediate takes 13 bytes, `exit` with an immediate 12
+        assert_eq!(assembled.code.len(), 25);
+        assert_eq!(assembled.code[5..13], 26u64.to_le_bytes());
+        assert_eq!(
+            assembled.data,
+            [1, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(assembled.image().len(), 40);
+        assert_eq!(assembled.symbol_at(0), Some("_start"));
+        assert_eq!(assembled.symbol_at(26), Some("values"));
+    }
+
+    #[test]
+    fn entry() {
+        let program = "helper:
+            ret $Z
+        _start:
+            cal :helper
+            exit 0
+        main:
+            nop
+        .data
+        value:
+            .byte 1
+        ";
+        let (parsed, _) = parse(program).unwrap();
+        let assemble = |entry| assemble_program(parsed.clone(), entry);
+
+        assert_eq!(assemble(DEFAULT_ENTRY).unwrap().entry, 5);
+        assert_eq!(assemble("main").unwrap().entry, 29);
+        assert_eq!(
+            assemble("start"),
+            Err(AssembleError::MissingEntry("start".to_string()))
+        );
+        assert_eq!(
+            assemble("value"),
+            Err(AssembleError::EntryOutsideText("value".to_string()))
+        );
+    }
 }
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use edu_asm_parser::directive::Section;
+
+use crate::{
+    object::{Object, ObjectSymbol},
+    program::{DebugLine, Program, Symbol},
+    AssembleError,
+};
+
+/// Combines `objects` into a program, that starts at the label `entry`.
+///
+/// The code sections are placed one after another, followed by the data sections. References
+/// resolve to a label of the same object first, then to a global label of any object. The entry
+/// label is always global, so exactly one object has to define it.
+pub fn link(objects: &[Object], entry: &str) -> Result<Program, AssembleError> {
+    let mut code = Vec::new();
+    let mut code_bases = Vec::with_capacity(objects.len());
+    for object in objects {
+        code_bases.push(code.len() as u64);
+        code.extend_from_slice(&object.code);
+    }
+
+    let mut data = Vec::new();
+    let mut data_bases = Vec::with_capacity(objects.len());
+    for object in objects {
+        // the data was assembled for the address right after the code of the object
+        let address = (code.len() + data.len()) as u64;
+        let padding = (object.code.len() as u64).wrapping_sub(address) & (object.data_align - 1);
+        data.resize(data.len() + padding as usize, 0);
+        data_bases.push((code.len() + data.len()) as u64);
+        data.extend_from_slice(&object.data);
+    }
+    let address = |index: usize, symbol: &ObjectSymbol| match symbol.section {
+        Section::Text => code_bases[index] + symbol.offset,
+        Section::Data => data_bases[index] + symbol.offset,
+    };
+
+    let mut globals: HashMap<&str, (usize, &ObjectSymbol)> = HashMap::new();
+    for (index, object) in objects.iter().enumerate() {
+        for symbol in object
+            .symbols
+            .iter()
+            .filter(|s| s.global || s.name == entry)
+        {
+            if let Some((first, _)) = globals.insert(&symbol.name, (index, symbol)) {
+                return Err(AssembleError::DuplicateSymbol(
+                    symbol.name.clone(),
+                    first,
+                    index,
+                ));
+            }
+        }
+    }
+
+    for (index, object) in objects.iter().enumerate() {
+        let locals: HashMap<&str, &ObjectSymbol> = object
+            .symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s))
+            .collect();
+        for relocation in &object.relocations {
+            let target = match locals.get(relocation.symbol.as_str()) {
+                Some(symbol) => address(index, symbol),
+                None => match globals.get(relocation.symbol.as_str()) {
+                    Some((defined, symbol)) => address(*defined, symbol),
+                    None => return Err(AssembleError::UndefinedSymbol(relocation.clone(), index)),
+                },
+            };
+            let start = (code_bases[index] + relocation.offset) as usize;
+            code[start..start + 8].copy_from_slice(&target.to_le_bytes());
+        }
+    }
+
+    let entry = match globals.get(entry) {
+        // a label after the last instruction of an object has nothing to execute
+        Some((index, symbol))
+            if symbol.section == Section::Text
+                && symbol.offset < objects[*index].code.len() as u64 =>
+        {
+            address(*index, symbol)
+        }
+        Some(_) => return Err(AssembleError::EntryOutsideText(entry.to_string())),
+        None => return Err(AssembleError::MissingEntry(entry.to_string())),
+    };
+
+    let debug_lines = objects
+        .iter()
+        .zip(&code_bases)
+        .enumerate()
+        .flat_map(|(index, (object, base))| {
+            object.debug_lines.iter().map(move |l| DebugLine {
+                offset: base + l.offset,
+                file: index as u64,
+                ..l.clone()
+            })
+        })
+        .collect();
+    let mut symbols: Vec<Symbol> = objects
+        .iter()
+        .enumerate()
+        .flat_map(|(index, object)| {
+            object.symbols.iter().map(move |s| Symbol {
+                name: s.name.clone(),
+                address: address(index, s),
+            })
+        })
+        .collect();
+    symbols.sort_by(|a, b| (a.address, &a.name).cmp(&(b.address, &b.name)));
+
+    Ok(Program {
+        code,
+        data,
+        entry,
+        symbols,
+        debug_lines,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use edu_asm_parser::parse;
+
+    use super::*;
+    use crate::{assemble_object, object::Relocation, DEFAULT_ENTRY};
+
+    fn object(source: &str) -> Object {
+        assemble_object(parse(source).unwrap().0)
+    }
+
+    #[test]
+    fn links_objects() {
+        let main = object(
+            ".extern print
+            .extern message
+            _start:
+                mov $G_0 :message
+                cal :print
+            .loop:
+                jmp :.loop
+            ",
+        );
+        let runtime = object(
+            ".global print
+            .global message
+            print:
+                jmp :.loop
+            .loop:
+                ret $Z
+            .data
+                .byte 1
+                .align 8
+            message:
+                .asciz \"hi\"
+            ",
+        );
+
+        let program = link(&[main, runtime], DEFAULT_ENTRY).unwrap();
+
+        // `mov` with an immediate takes 13 bytes, `cal` and `jmp` 12 and `ret` 5
+        assert_eq!(program.code.len(), 54);
+        assert_eq!(program.entry, 0);
+        // the data of the runtime was aligned for address 17, right after its code
+        assert_eq!(program.data, [0, 0, 0, 1, 0, 0, 0, 0, 0, 0, b'h', b'i', 0]);
+        let immediate = |offset: usize| {
+            u64::from_le_bytes(program.code[offset..offset + 8].try_into().unwrap())
+        };
+        assert_eq!(immediate(5), 64);
+        assert_eq!(immediate(17), 37);
+        assert_eq!(immediate(29), 25);
+        assert_eq!(immediate(41), 49);
+        assert_eq!(program.symbol_at(37), Some("print"));
+        assert_eq!(program.symbol_at(64), Some("message"));
+        let location = |offset| {
+            program
+                .debug_line_at(offset)
+                .map(|l| (l.file, l.line, l.column))
+        };
+        assert_eq!(location(13), Some((0, 4, 16)));
+        assert_eq!(location(37), Some((1, 3, 16)));
+    }
+
+    #[test]
+    fn symbol_errors() {
+        let start = || object("_start:\n\tcal :helper\n.global helper\nhelper:\n\tret $Z\n");
+
+        assert_eq!(
+            link(
+                &[start(), object(".global helper\nhelper:\n\tnop\n")],
+                "_start"
+            ),
+            Err(AssembleError::DuplicateSymbol("helper".to_string(), 0, 1))
+        );
+        assert_eq!(
+            link(&[start(), start()], "_start"),
+            Err(AssembleError::DuplicateSymbol("_start".to_string(), 0, 1))
+        );
+        assert_eq!(
+            link(&[object(".extern f\n_start:\n    cal :f\n")], "_start"),
+            Err(AssembleError::UndefinedSymbol(
+                Relocation {
+                    offset: 4,
+                    symbol: "f".to_string(),
+                    line: 2,
+                    column: 8
+                },
+                0
+            ))
+        );
+        assert_eq!(
+            link(&[start()], "main"),
+            Err(AssembleError::MissingEntry("main".to_string()))
+        );
+        // local labels of other objects aren't visible
+        assert!(matches!(
+            link(
+                &[
+                    object(".extern f\n_start:\n\tcal :f\n"),
+                    object("f:\n\tret $Z\n")
+                ],
+                "_start"
+            ),
+            Err(AssembleError::UndefinedSymbol(_, 0))
+        ));
+    }
+}
@@ -116,6 +116,20 @@ impl OperationMode {
         }
     }
 
+    pub fn get(&self, index: usize) -> RegisterLiteral {
+        match index {
+            0 => self.zero,
+            1 => self.one,
+            2 => self.two,
+            3 => self.three,
+            4 => self.four,
+            5 => self.five,
+            6 => self.six,
+            7 => self.seven,
+            _ => panic!("Invalid index"),
+        }
+    }
+
     pub fn new(inp: &[RegisterLiteral]) -> Self {
         assert!(inp.len() <= 8);
 
@@ -0,0 +1,257 @@
+use edu_asm_parser::directive::{Section, MAX_ALIGNMENT};
+
+use crate::program::{
+    write_header, write_section, write_string, DebugLine, ProgramError, Reader, CODE_SECTION,
+    DATA_SECTION, DEBUG_LINE_SECTION,
+};
+
+/// The first bytes of every object file
+pub const OBJECT_MAGIC: [u8; 4] = *b"EDUO";
+
+const OBJECT_SYMBOL_SECTION: u32 = 5;
+const RELOCATION_SECTION: u32 = 6;
+
+/// A label defined in an object file
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ObjectSymbol {
+    pub name: String,
+    pub section: Section,
+    /// Byte offset of the label in its section
+    pub offset: u64,
+    /// Whether the label is declared `.global` and can be referenced by other objects
+    pub global: bool,
+}
+
+/// A label reference in the code, that is filled in with the address of the label when linking
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Relocation {
+    /// Byte offset of the 8-byte immediate in the code section
+    pub offset: u64,
+    /// The referenced label, a symbol of the same object or a global symbol of another one
+    pub symbol: String,
+    /// Line of the label reference in the source file, starting at 0
+    pub line: u64,
+    /// Column of the label reference in its line in characters, starting at 0
+    pub column: u64,
+}
+
+/// A separately assembled source file, combined with other objects into a [`Program`] by
+/// [`link`].
+///
+/// The data section was assembled for an address right after the code of the object, the linker
+/// keeps its position relative to `data_align`.
+///
+/// [`Program`]: crate::program::Program
+/// [`link`]: crate::link::link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    pub code: Vec<u8>,
+    pub data: Vec<u8>,
+    /// The largest alignment of the data section, a power of two
+    pub data_align: u64,
+    pub symbols: Vec<ObjectSymbol>,
+    pub relocations: Vec<Relocation>,
+    /// Source locations of the instructions, ordered by offset
+    pub debug_lines: Vec<DebugLine>,
+}
+
+impl Object {
+    /// Encodes the object as an object file, see `BYTECODE.md`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut ret = write_header(&OBJECT_MAGIC, self.data_align);
+        write_section(&mut ret, CODE_SECTION, &self.code);
+        if !self.data.is_empty() {
+            write_section(&mut ret, DATA_SECTION, &self.data);
+        }
+        if !self.symbols.is_empty() {
+            let mut payload = Vec::new();
+            for symbol in &self.symbols {
+                payload.push(match symbol.section {
+                    Section::Text => CODE_SECTION as u8,
+                    Section::Data => DATA_SECTION as u8,
+                });
+                payload.push(symbol.global as u8);
+                payload.extend_from_slice(&symbol.offset.to_le_bytes());
+                write_string(&mut payload, &symbol.name);
+            }
+            write_section(&mut ret, OBJECT_SYMBOL_SECTION, &payload);
+        }
+        if !self.relocations.is_empty() {
+            let mut payload = Vec::new();
+            for relocation in &self.relocations {
+                payload.extend_from_slice(&relocation.offset.to_le_bytes());
+                payload.extend_from_slice(&relocation.line.to_le_bytes());
+                payload.extend_from_slice(&relocation.column.to_le_bytes());
+                write_string(&mut payload, &relocation.symbol);
+            }
+            write_section(&mut ret, RELOCATION_SECTION, &payload);
+        }
+        if !self.debug_lines.is_empty() {
+            let mut payload = Vec::new();
+            for line in &self.debug_lines {
+                line.write(&mut payload);
+            }
+            write_section(&mut ret, DEBUG_LINE_SECTION, &payload);
+        }
+        ret
+    }
+
+    /// Decodes and validates an object file written by [`Object::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Object, ProgramError> {
+        let mut reader = Reader::new(bytes);
+        let mut ret = Object {
+            code: Vec::new(),
+            data: Vec::new(),
+            data_align: reader.header(&OBJECT_MAGIC)?,
+            symbols: Vec::new(),
+            relocations: Vec::new(),
+            debug_lines: Vec::new(),
+        };
+        if !ret.data_align.is_power_of_two() || ret.data_align > MAX_ALIGNMENT {
+            return Err(ProgramError::InvalidAlignment(ret.data_align));
+        }
+
+        let seen = reader.sections(|kind, mut payload| {
+            match kind {
+                CODE_SECTION => ret.code = payload.bytes.to_vec(),
+                DATA_SECTION => ret.data = payload.bytes.to_vec(),
+                OBJECT_SYMBOL_SECTION => {
+                    while !payload.is_empty() {
+                        let section = payload.u8("a symbol")?;
+                        let global = payload.u8("a symbol")?;
+                        let offset = payload.u64("a symbol")?;
+                        let name = payload.string("a symbol")?;
+                        let section = match (section as u32, global) {
+                            (CODE_SECTION, 0 | 1) => Section::Text,
+                            (DATA_SECTION, 0 | 1) => Section::Data,
+                            _ => return Err(ProgramError::InvalidSymbol(name)),
+                        };
+                        ret.symbols.push(ObjectSymbol {
+                            name,
+                            section,
+                            offset,
+                            global: global == 1,
+                        });
+                    }
+                }
+                RELOCATION_SECTION => {
+                    while !payload.is_empty() {
+                        ret.relocations.push(Relocation {
+                            offset: payload.u64("a relocation")?,
+                            line: payload.u64("a relocation")?,
+                            column: payload.u64("a relocation")?,
+                            symbol: payload.string("a relocation")?,
+                        });
+                    }
+                }
+                DEBUG_LINE_SECTION => {
+                    while !payload.is_empty() {
+                        ret.debug_lines.push(DebugLine::read(&mut payload)?);
+                    }
+                }
+                _ => return Err(ProgramError::UnknownSection(kind)),
+            }
+            Ok(())
+        })?;
+
+        if !seen.contains(&CODE_SECTION) {
+            return Err(ProgramError::MissingCode);
+        }
+        let section_len = |section| match section {
+            Section::Text => ret.code.len() as u64,
+            Section::Data => ret.data.len() as u64,
+        };
+        if let Some(symbol) = ret
+            .symbols
+            .iter()
+            .find(|s| s.offset > section_len(s.section))
+        {
+            return Err(ProgramError::InvalidSymbol(symbol.name.clone()));
+        }
+        if let Some(relocation) = ret.relocations.iter().find(|r| {
+            r.offset
+                .checked_add(8)
+                .is_none_or(|end| end > section_len(Section::Text))
+        }) {
+            return Err(ProgramError::InvalidRelocation(relocation.offset));
+        }
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object() -> Object {
+        Object {
+            code: vec![0; 12],
+            data: vec![1, 2],
+            data_align: 8,
+            symbols: vec![
+                ObjectSymbol {
+                    name: "main".to_string(),
+                    section: Section::Text,
+                    offset: 0,
+                    global: true,
+                },
+                ObjectSymbol {
+                    name: "values".to_string(),
+                    section: Section::Data,
+                    offset: 2,
+                    global: false,
+                },
+            ],
+            relocations: vec![Relocation {
+                offset: 4,
+                symbol: "print".to_string(),
+                line: 1,
+                column: 8,
+            }],
+            debug_lines: vec![DebugLine {
+                offset: 0,
+                file: 0,
+                line: 1,
+                column: 4,
+                operands: vec![8],
+            }],
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let object = object();
+
+        assert_eq!(Object::from_bytes(&object.to_bytes()), Ok(object));
+    }
+
+    #[test]
+    fn validation() {
+        let modified = |change: fn(&mut Object)| {
+            let mut object = object();
+            change(&mut object);
+            Object::from_bytes(&object.to_bytes())
+        };
+
+        assert_eq!(
+            modified(|o| o.data_align = 3),
+            Err(ProgramError::InvalidAlignment(3))
+        );
+        assert_eq!(
+            modified(|o| o.data_align = 1 << 62),
+            Err(ProgramError::InvalidAlignment(1 << 62))
+        );
+        assert_eq!(
+            modified(|o| o.symbols[1].offset = 3),
+            Err(ProgramError::InvalidSymbol("values".to_string()))
+        );
+        assert_eq!(
+            modified(|o| o.relocations[0].offset = 5),
+            Err(ProgramError::InvalidRelocation(5))
+        );
+        assert_eq!(
+            Object::from_bytes(&crate::program::Program::default().to_bytes()),
+            Err(ProgramError::InvalidMagic)
+        );
+    }
+}
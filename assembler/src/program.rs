@@ -0,0 +1,415 @@
+use thiserror::Error;
+
+/// The first bytes of every bytecode file
+pub const MAGIC: [u8; 4] = *b"EDUA";
+/// Version of the container format described in `BYTECODE.md`
+pub const FORMAT_VERSION: u16 = 2;
+/// Revision of the instruction set the code section is encoded in
+pub const ISA_REVISION: u16 = 1;
+/// Size of the fixed header: magic, format version, ISA revision and entry point or, in object
+/// files, data alignment
+pub const HEADER_SIZE: usize = 16;
+
+pub(crate) const CODE_SECTION: u32 = 1;
+pub(crate) const DATA_SECTION: u32 = 2;
+const SYMBOL_SECTION: u32 = 3;
+pub(crate) const DEBUG_LINE_SECTION: u32 = 4;
+
+/// A label of the assembled program
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Symbol {
+    pub name: String,
+    /// Address of the label, data labels point behind the code section
+    pub address: u64,
+}
+
+/// Maps an instruction to the source location it was assembled from
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DebugLine {
+    /// Byte offset of the instruction in the code section
+    pub offset: u64,
+    /// Index of the linked object the instruction was assembled from, 0 for a single source file
+    pub file: u64,
+    /// Line in the source file, starting at 0
+    pub line: u64,
+    /// Column of the instruction in its line in characters, starting at 0
+    pub column: u64,
+    /// Columns of the operands in the order of the source
+    pub operands: Vec<u64>,
+}
+
+impl DebugLine {
+    pub(crate) fn write(&self, ret: &mut Vec<u8>) {
+        ret.extend_from_slice(&self.offset.to_le_bytes());
+        ret.extend_from_slice(&self.file.to_le_bytes());
+        ret.extend_from_slice(&self.line.to_le_bytes());
+        ret.extend_from_slice(&self.column.to_le_bytes());
+        ret.extend_from_slice(&(self.operands.len() as u64).to_le_bytes());
+        for column in &self.operands {
+            ret.extend_from_slice(&column.to_le_bytes());
+        }
+    }
+
+    pub(crate) fn read(reader: &mut Reader) -> Result<DebugLine, ProgramError> {
+        Ok(DebugLine {
+            offset: reader.u64("a debug line")?,
+            file: reader.u64("a debug line")?,
+            line: reader.u64("a debug line")?,
+            column: reader.u64("a debug line")?,
+            operands: {
+                let count = reader.u64("a debug line")?;
+                (0..count)
+                    .map(|_| reader.u64("a debug line"))
+                    .collect::<Result<_, _>>()?
+            },
+        })
+    }
+}
+
+/// An assembled program, the data section is loaded directly after the code section
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Program {
+    pub code: Vec<u8>,
+    pub data: Vec<u8>,
+    /// Byte offset of the first instruction that is executed
+    pub entry: u64,
+    pub symbols: Vec<Symbol>,
+    /// Source lines of the instructions, ordered by offset, empty if unknown
+    pub debug_lines: Vec<DebugLine>,
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ProgramError {
+    #[error("the file isn't an edu asm file of the expected kind, its magic bytes differ")]
+    InvalidMagic,
+    #[error("the file uses format version `{0}`, only version `{FORMAT_VERSION}` is supported")]
+    UnsupportedVersion(u16),
+    #[error(
+        "the code uses instruction set revision `{0}`, only revision `{ISA_REVISION}` is supported"
+    )]
+    UnsupportedIsa(u16),
+    #[error("the file ends at byte `{0}`, while reading {1}")]
+    Truncated(usize, &'static str),
+    #[error("section `{0}` is unknown")]
+    UnknownSection(u32),
+    #[error("section `{0}` occurs more than once")]
+    DuplicateSection(u32),
+    #[error("the code section is missing")]
+    MissingCode,
+    #[error("symbol name at byte `{0}` isn't valid utf-8")]
+    InvalidSymbolName(usize),
+    #[error("the entry point `{0}` is outside of the code section")]
+    EntryOutOfBounds(u64),
+    #[error("the data alignment `{0}` isn't a power of two or too large")]
+    InvalidAlignment(u64),
+    #[error("symbol `{0}` has an invalid section, binding or offset")]
+    InvalidSymbol(String),
+    #[error("the relocation at byte `{0}` is outside of the code section")]
+    InvalidRelocation(u64),
+}
+
+/// Reads little endian values from a bytecode file, keeping track of the position for errors
+pub(crate) struct Reader<'a> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) pos: usize,
+    /// Position of `bytes` in the file
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader {
+            bytes,
+            pos: 0,
+            offset: 0,
+        }
+    }
+
+    fn take(&mut self, len: usize, what: &'static str) -> Result<&'a [u8], ProgramError> {
+        let ret = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.pos..end))
+            .ok_or(ProgramError::Truncated(
+                self.offset + self.bytes.len(),
+                what,
+            ))?;
+        self.pos += len;
+        Ok(ret)
+    }
+
+    pub(crate) fn u8(&mut self, what: &'static str) -> Result<u8, ProgramError> {
+        Ok(self.take(1, what)?[0])
+    }
+
+    fn u16(&mut self, what: &'static str) -> Result<u16, ProgramError> {
+        Ok(u16::from_le_bytes(self.take(2, what)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self, what: &'static str) -> Result<u32, ProgramError> {
+        Ok(u32::from_le_bytes(self.take(4, what)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self, what: &'static str) -> Result<u64, ProgramError> {
+        Ok(u64::from_le_bytes(self.take(8, what)?.try_into().unwrap()))
+    }
+
+    /// Reads a length, that has to fit into the remaining file
+    fn len(&mut self, what: &'static str) -> Result<usize, ProgramError> {
+        let len = self.u64(what)?;
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.bytes.len() - self.pos)
+            .ok_or(ProgramError::Truncated(
+                self.offset + self.bytes.len(),
+                what,
+            ))
+    }
+
+    /// Reads a length prefixed utf-8 string
+    pub(crate) fn string(&mut self, what: &'static str) -> Result<String, ProgramError> {
+        let len = self.len(what)?;
+        let start = self.offset + self.pos;
+        std::str::from_utf8(self.take(len, what)?)
+            .map(str::to_string)
+            .map_err(|_| ProgramError::InvalidSymbolName(start))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    /// Reads the header written by [`write_header`], returns the value stored after the revisions
+    pub(crate) fn header(&mut self, magic: &[u8; 4]) -> Result<u64, ProgramError> {
+        if self.take(magic.len(), "the header").ok() != Some(&magic[..]) {
+            return Err(ProgramError::InvalidMagic);
+        }
+        let version = self.u16("the header")?;
+        if version != FORMAT_VERSION {
+            return Err(ProgramError::UnsupportedVersion(version));
+        }
+        let isa = self.u16("the header")?;
+        if isa != ISA_REVISION {
+            return Err(ProgramError::UnsupportedIsa(isa));
+        }
+        self.u64("the header")
+    }
+
+    /// Reads the sections following the header, passing the kind and the payload of each to
+    /// `section`, returns the kinds that were read
+    pub(crate) fn sections(
+        &mut self,
+        mut section: impl FnMut(u32, Reader<'a>) -> Result<(), ProgramError>,
+    ) -> Result<Vec<u32>, ProgramError> {
+        let mut seen = Vec::new();
+        while !self.is_empty() {
+            let kind = self.u32("a section header")?;
+            let len = self.len("a section header")?;
+            if seen.contains(&kind) {
+                return Err(ProgramError::DuplicateSection(kind));
+            }
+            seen.push(kind);
+            let offset = self.offset + self.pos;
+            let payload = Reader {
+                bytes: self.take(len, "a section")?,
+                pos: 0,
+                offset,
+            };
+            section(kind, payload)?;
+        }
+        Ok(seen)
+    }
+}
+
+/// Writes the header of a bytecode or object file, `value` follows the revisions
+pub(crate) fn write_header(magic: &[u8; 4], value: u64) -> Vec<u8> {
+    let mut ret = Vec::with_capacity(HEADER_SIZE);
+    ret.extend_from_slice(magic);
+    ret.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    ret.extend_from_slice(&ISA_REVISION.to_le_bytes());
+    ret.extend_from_slice(&value.to_le_bytes());
+    ret
+}
+
+pub(crate) fn write_section(ret: &mut Vec<u8>, kind: u32, payload: &[u8]) {
+    ret.extend_from_slice(&kind.to_le_bytes());
+    ret.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    ret.extend_from_slice(payload);
+}
+
+/// Appends a length prefixed string
+pub(crate) fn write_string(ret: &mut Vec<u8>, value: &str) {
+    ret.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    ret.extend_from_slice(value.as_bytes());
+}
+
+impl Program {
+    /// Returns the memory image of the program, the code followed by the data
+    pub fn image(&self) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(self.code.len() + self.data.len());
+        ret.extend_from_slice(&self.code);
+        ret.extend_from_slice(&self.data);
+        ret
+    }
+
+    /// Returns the name of the first symbol at `address`
+    pub fn symbol_at(&self, address: u64) -> Option<&str> {
+        self.symbols
+            .iter()
+            .find(|s| s.address == address)
+            .map(|s| s.name.as_str())
+    }
+
+    /// Returns the source location of the instruction at `offset`
+    pub fn debug_line_at(&self, offset: u64) -> Option<&DebugLine> {
+        self.debug_lines
+            .binary_search_by_key(&offset, |l| l.offset)
+            .ok()
+            .map(|index| &self.debug_lines[index])
+    }
+
+    /// Encodes the program as a bytecode file, see `BYTECODE.md`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut ret = write_header(&MAGIC, self.entry);
+        write_section(&mut ret, CODE_SECTION, &self.code);
+        if !self.data.is_empty() {
+            write_section(&mut ret, DATA_SECTION, &self.data);
+        }
+        if !self.symbols.is_empty() {
+            let mut payload = Vec::new();
+            for symbol in &self.symbols {
+                payload.extend_from_slice(&symbol.address.to_le_bytes());
+                write_string(&mut payload, &symbol.name);
+            }
+            write_section(&mut ret, SYMBOL_SECTION, &payload);
+        }
+        if !self.debug_lines.is_empty() {
+            let mut payload = Vec::new();
+            for line in &self.debug_lines {
+                line.write(&mut payload);
+            }
+            write_section(&mut ret, DEBUG_LINE_SECTION, &payload);
+        }
+        ret
+    }
+
+    /// Decodes and validates a bytecode file written by [`Program::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Program, ProgramError> {
+        let mut reader = Reader::new(bytes);
+        let mut ret = Program {
+            entry: reader.header(&MAGIC)?,
+            ..Program::default()
+        };
+
+        let seen = reader.sections(|kind, mut payload| {
+            match kind {
+                CODE_SECTION => ret.code = payload.bytes.to_vec(),
+                DATA_SECTION => ret.data = payload.bytes.to_vec(),
+                SYMBOL_SECTION => {
+                    while !payload.is_empty() {
+                        let address = payload.u64("a symbol")?;
+                        let name = payload.string("a symbol")?;
+                        ret.symbols.push(Symbol { name, address });
+                    }
+                }
+                DEBUG_LINE_SECTION => {
+                    while !payload.is_empty() {
+                        ret.debug_lines.push(DebugLine::read(&mut payload)?);
+                    }
+                }
+                _ => return Err(ProgramError::UnknownSection(kind)),
+            }
+            Ok(())
+        })?;
+
+        if !seen.contains(&CODE_SECTION) {
+            return Err(ProgramError::MissingCode);
+        }
+        if ret.entry >= ret.code.len() as u64 && !(ret.entry == 0 && ret.code.is_empty()) {
+            return Err(ProgramError::EntryOutOfBounds(ret.entry));
+        }
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program() -> Program {
+        Program {
+            code: vec![1, 2, 3],
+            data: vec![4, 5],
+            entry: 2,
+            symbols: vec![Symbol {
+                name: "_start".to_string(),
+                address: 2,
+            }],
+            debug_lines: vec![DebugLine {
+                offset: 2,
+                file: 0,
+                line: 7,
+                column: 4,
+                operands: vec![8, 12],
+            }],
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let program = program();
+
+        let bytes = program.to_bytes();
+
+        assert_eq!(Program::from_bytes(&bytes), Ok(program.clone()));
+        let code_only = Program {
+            entry: 0,
+            ..Program::default()
+        };
+        assert_eq!(Program::from_bytes(&code_only.to_bytes()), Ok(code_only));
+        assert_eq!(program.symbol_at(2), Some("_start"));
+        assert_eq!(
+            program.debug_line_at(2).map(|l| (l.line, l.column)),
+            Some((7, 4))
+        );
+        assert_eq!(program.debug_line_at(1), None);
+    }
+
+    #[test]
+    fn validation() {
+        let bytes = program().to_bytes();
+        let modified = |index: usize, value: u8| {
+            let mut bytes = bytes.clone();
+            bytes[index] = value;
+            Program::from_bytes(&bytes)
+        };
+
+        assert_eq!(modified(0, b'X'), Err(ProgramError::InvalidMagic));
+        assert_eq!(modified(4, 1), Err(ProgramError::UnsupportedVersion(1)));
+        assert_eq!(modified(6, 9), Err(ProgramError::UnsupportedIsa(9)));
+        assert_eq!(modified(8, 3), Err(ProgramError::EntryOutOfBounds(3)));
+        assert_eq!(
+            modified(HEADER_SIZE, 9),
+            Err(ProgramError::UnknownSection(9))
+        );
+        assert_eq!(
+            modified(HEADER_SIZE + 12 + 3, 1),
+            Err(ProgramError::DuplicateSection(1))
+        );
+        assert_eq!(
+            Program::from_bytes(&bytes[..HEADER_SIZE]),
+            Err(ProgramError::MissingCode)
+        );
+        assert_eq!(
+            Program::from_bytes(&bytes[..2]),
+            Err(ProgramError::InvalidMagic)
+        );
+        for len in [HEADER_SIZE - 1, HEADER_SIZE + 5, bytes.len() - 1] {
+            assert!(
+                matches!(Program::from_bytes(&bytes[..len]), Err(ProgramError::Truncated(l, _)) if l == len),
+                "{}",
+                len
+            );
+        }
+    }
+}
@@ -50,3 +50,24 @@ pub fn encode_register_token(token: &RegisterToken) -> u8 {
         RegisterToken::Error => E_CODE,
     }
 }
+
+pub fn decode_register_token(code: u8) -> Option<RegisterToken> {
+    let ret = match code {
+        G_0_CODE => RegisterToken::GeneralPurpose(0),
+        G_1_CODE => RegisterToken::GeneralPurpose(1),
+        G_2_CODE => RegisterToken::GeneralPurpose(2),
+        G_3_CODE => RegisterToken::GeneralPurpose(3),
+        G_4_CODE => RegisterToken::GeneralPurpose(4),
+        G_5_CODE => RegisterToken::GeneralPurpose(5),
+        G_6_CODE => RegisterToken::GeneralPurpose(6),
+        G_7_CODE => RegisterToken::GeneralPurpose(7),
+        S_B_CODE => RegisterToken::StackBase,
+        S_E_CODE => RegisterToken::StackEnd,
+        R_CODE => RegisterToken::Return,
+        I_CODE => RegisterToken::Instruction,
+        Z_CODE => RegisterToken::Zero,
+        E_CODE => RegisterToken::Error,
+        _ => return None,
+    };
+    Some(ret)
+}
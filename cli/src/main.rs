@@ -0,0 +1,607 @@
+use std::{
+    fs,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use edu_asm_assembler::{
+    assemble_object, assemble_program,
+    link::link,
+    object::{Object, Relocation},
+    program::{DebugLine, Program, ProgramError},
+    AssembleError, DEFAULT_ENTRY,
+};
+use edu_asm_disassembler::{disassemble_to_source, DisassembleError};
+use edu_asm_interpreter::{
+    config::{Config, DumpConfig, DumpFormat, ErrorMode, OverflowMode},
+    error::RuntimeError,
+    io::StdIo,
+    StopReason, Vm,
+};
+use edu_asm_parser::{
+    diagnostic::{Diagnostic, Source},
+    parse_all_with_entry,
+    span::Span,
+    ParseError, PureElement,
+};
+use thiserror::Error;
+
+/// File extension of edu asm source files
+const SOURCE_EXTENSION: &str = "edu";
+/// File extension of assembled bytecode files
+const BYTECODE_EXTENSION: &str = "bin";
+/// File extension of object files, which are linked into bytecode files
+const OBJECT_EXTENSION: &str = "obj";
+
+#[derive(Parser)]
+#[command(
+    name = "edu-asm",
+    version,
+    about = "Assembler, interpreter and disassembler for edu asm"
+)]
+struct Cli {
+    /// Format of errors: colored or plain text, or one JSON object per line for tools
+    #[arg(long, global = true, value_enum, default_value_t = MessageFormat::Auto)]
+    message_format: MessageFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Assemble a source file into bytecode
+    Assemble {
+        /// Source file to assemble
+        input: PathBuf,
+        /// Output file, defaults to the input file with the extension `.bin`, or `.obj` for
+        /// object files
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Label the execution starts at
+        #[arg(long, default_value = DEFAULT_ENTRY)]
+        entry: String,
+        /// Write an object file, that is linked with others by `link`
+        #[arg(short = 'c', long)]
+        object: bool,
+    },
+    /// Link source (`.edu`) and object (`.obj`) files into bytecode
+    Link {
+        /// Source and object files to link
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// Output file, defaults to the first input file with the extension `.bin`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Label the execution starts at
+        #[arg(long, default_value = DEFAULT_ENTRY)]
+        entry: String,
+    },
+    /// Run a source file (`.edu`) or a bytecode file, exiting with the code passed to `exit`
+    Run {
+        /// Source or bytecode file to run, multiple source and object files are linked
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// Label the execution starts at, if source or object files are run
+        #[arg(long, default_value = DEFAULT_ENTRY)]
+        entry: String,
+        /// Format of the state snapshots written by `dump`
+        #[arg(long, value_enum, default_value_t = DumpFormatArg::Text)]
+        dump_format: DumpFormatArg,
+        /// Include the touched memory pages in the snapshots written by `dump`
+        #[arg(long)]
+        dump_memory: bool,
+        /// Whether faults stop the program or set `$E` and continue
+        #[arg(long, value_enum, default_value_t = ErrorModeArg::Trap)]
+        errors: ErrorModeArg,
+        /// Raise a fault on arithmetic overflow instead of wrapping around
+        #[arg(long)]
+        checked: bool,
+        /// Maximum size of the stack in bytes
+        #[arg(long, default_value_t = Config::default().stack_size)]
+        stack_size: u64,
+    },
+    /// Check a source or bytecode file for errors without running it
+    Check {
+        /// Source or bytecode file to check, multiple source and object files are checked for
+        /// linking errors
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// Label the execution starts at
+        #[arg(long, default_value = DEFAULT_ENTRY)]
+        entry: String,
+    },
+    /// Disassemble a bytecode file into source
+    Disassemble {
+        /// Bytecode file to disassemble
+        input: PathBuf,
+        /// Output file, defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MessageFormat {
+    /// Colored text if stderr is a terminal and `NO_COLOR` isn't set, plain text otherwise
+    Auto,
+    Colored,
+    Plain,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DumpFormatArg {
+    Text,
+    Json,
+}
+
+impl From<DumpFormatArg> for DumpFormat {
+    fn from(format: DumpFormatArg) -> Self {
+        match format {
+            DumpFormatArg::Text => DumpFormat::Text,
+            DumpFormatArg::Json => DumpFormat::Json,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ErrorModeArg {
+    Trap,
+    Flag,
+}
+
+impl From<ErrorModeArg> for ErrorMode {
+    fn from(mode: ErrorModeArg) -> Self {
+        match mode {
+            ErrorModeArg::Trap => ErrorMode::Trap,
+            ErrorModeArg::Flag => ErrorMode::Flag,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum CliError {
+    #[error("couldn't read `{0}`: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("couldn't write `{0}`: {1}")]
+    Write(PathBuf, std::io::Error),
+    #[error("couldn't parse `{}`: {}", .0.display(), .1[0])]
+    Parse(PathBuf, Vec<ParseError>),
+    #[error("couldn't assemble `{0}`: {1}")]
+    Assemble(PathBuf, AssembleError),
+    #[error("couldn't link: {}", render_link_error(.0, .1))]
+    Link(Vec<PathBuf>, AssembleError),
+    #[error("couldn't load `{0}`: {1}")]
+    Load(PathBuf, ProgramError),
+    #[error("couldn't disassemble `{0}`: {1}")]
+    Disassemble(PathBuf, DisassembleError),
+    #[error("{}", render_runtime_error(.0, .1))]
+    Runtime(Vec<PathBuf>, Box<RuntimeError>),
+}
+
+/// Names the objects of a linking error by their files
+fn render_link_error(paths: &[PathBuf], error: &AssembleError) -> String {
+    let path = |index: &usize| paths[*index].display();
+    match error {
+        AssembleError::DuplicateSymbol(name, first, second) => format!(
+            "label `{}` is defined in `{}` and `{}`",
+            name,
+            path(first),
+            path(second)
+        ),
+        AssembleError::UndefinedSymbol(relocation, index) => format!(
+            "label `{}` is used at `{}:{}:{}`, but never defined",
+            relocation.symbol,
+            path(index),
+            relocation.line + 1,
+            relocation.column + 1
+        ),
+        e => e.to_string(),
+    }
+}
+
+/// Points at the source line of the faulting instruction, if it was assembled from one of `paths`
+fn render_runtime_error(paths: &[PathBuf], error: &RuntimeError) -> String {
+    let source = error
+        .location
+        .as_ref()
+        .and_then(|l| paths.get(l.file as usize).map(|path| (path, l)))
+        .filter(|(path, _)| is_source(path));
+    match source {
+        Some((path, location)) => format!(
+            "{}:{}:{}: {}",
+            path.display(),
+            location.line + 1,
+            fault_operand(error, location).unwrap_or(location.column) + 1,
+            error
+        ),
+        None => format!("running `{}` failed: {}", paths[0].display(), error),
+    }
+}
+
+/// Converts `error` into diagnostics, each with the source file it points into
+fn diagnostics(error: &CliError) -> Vec<(Diagnostic, Option<&Path>)> {
+    match error {
+        CliError::Parse(path, errors) => errors
+            .iter()
+            .map(|e| (e.diagnostic(), Some(path.as_path())))
+            .collect(),
+        CliError::Assemble(path, AssembleError::UndefinedSymbol(relocation, _)) => {
+            vec![undefined_symbol_diagnostic(error, path, relocation)]
+        }
+        CliError::Link(paths, AssembleError::UndefinedSymbol(relocation, index)) => {
+            vec![undefined_symbol_diagnostic(
+                error,
+                &paths[*index],
+                relocation,
+            )]
+        }
+        CliError::Runtime(paths, e) => {
+            let location = e
+                .location
+                .as_ref()
+                .and_then(|l| Some((paths.get(l.file as usize)?, l)))
+                .filter(|(path, _)| is_source(path))
+                .and_then(|(path, l)| {
+                    let span = match fault_operand(e, l) {
+                        Some(column) => (
+                            locate(path, |s| {
+                                Span::operand_at(s, l.line as usize, column as usize)
+                            })?,
+                            "raised by this operand",
+                        ),
+                        None => (
+                            locate(path, |s| Span::at(s, l.line as usize, l.column as usize))?,
+                            "raised by this instruction",
+                        ),
+                    };
+                    Some((path, span))
+                });
+            match location {
+                Some((path, (span, label))) => vec![(
+                    Diagnostic::error(e.kind.to_string())
+                        .with_span(span, label)
+                        .with_note(format!("the instruction is at byte `{}`", e.index)),
+                    Some(path.as_path()),
+                )],
+                None => vec![(Diagnostic::error(error.to_string()), None)],
+            }
+        }
+        e => vec![(Diagnostic::error(e.to_string()), None)],
+    }
+}
+
+fn undefined_symbol_diagnostic<'a>(
+    error: &CliError,
+    path: &'a Path,
+    relocation: &Relocation,
+) -> (Diagnostic, Option<&'a Path>) {
+    let (line, column) = (relocation.line as usize, relocation.column as usize);
+    match locate(path, |s| Span::operand_at(s, line, column)) {
+        Some(span) => (
+            Diagnostic::error(format!("label `{}` is never defined", relocation.symbol))
+                .with_span(span, "undefined label")
+                .with_help(Some(format!(
+                    "define it in one of the linked files and declare it `.global {}`",
+                    relocation.symbol
+                ))),
+            Some(path),
+        ),
+        None => (Diagnostic::error(error.to_string()), None),
+    }
+}
+
+/// Returns the span `at` finds in the source file `path`
+fn locate(path: &Path, at: impl FnOnce(&str) -> Option<Span>) -> Option<Span> {
+    if !is_source(path) {
+        return None;
+    }
+    at(&fs::read_to_string(path).ok()?)
+}
+
+/// Returns the column of the operand causing `error`, if it is known from `location`
+fn fault_operand(error: &RuntimeError, location: &DebugLine) -> Option<u64> {
+    location.operands.get(error.operand?).copied()
+}
+
+/// Prints the diagnostics of `error` to stderr
+fn report(error: &CliError, format: MessageFormat) {
+    print_diagnostics(diagnostics(error), format);
+}
+
+fn print_diagnostics(diagnostics: Vec<(Diagnostic, Option<&Path>)>, format: MessageFormat) {
+    let colored = match format {
+        MessageFormat::Auto => {
+            std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+        }
+        MessageFormat::Colored => true,
+        MessageFormat::Plain | MessageFormat::Json => false,
+    };
+    for (diagnostic, path) in diagnostics {
+        if format == MessageFormat::Json {
+            eprintln!("{}", diagnostic.to_json(path));
+            continue;
+        }
+        let text = path.and_then(|p| fs::read_to_string(p).ok());
+        let source = path
+            .zip(text.as_deref())
+            .map(|(path, text)| Source { path, text });
+        eprintln!("{}", diagnostic.render(source, colored));
+    }
+}
+
+fn read_source(path: &Path) -> Result<String, CliError> {
+    fs::read_to_string(path).map_err(|e| CliError::Read(path.to_path_buf(), e))
+}
+
+/// Parses a source file, printing its warnings and failing with all errors in it
+fn parse_source(
+    path: &Path,
+    entry: &str,
+    format: MessageFormat,
+) -> Result<Vec<PureElement>, CliError> {
+    let output = parse_all_with_entry(&read_source(path)?, entry);
+    let warnings = output
+        .warnings
+        .iter()
+        .map(|w| (w.diagnostic(), Some(path)))
+        .collect();
+    print_diagnostics(warnings, format);
+    if !output.errors.is_empty() {
+        return Err(CliError::Parse(path.to_path_buf(), output.errors));
+    }
+    Ok(output.elements)
+}
+
+fn read_bytecode(path: &Path) -> Result<Program, CliError> {
+    let bytes = fs::read(path).map_err(|e| CliError::Read(path.to_path_buf(), e))?;
+    Program::from_bytes(&bytes).map_err(|e| CliError::Load(path.to_path_buf(), e))
+}
+
+fn assemble_source(path: &Path, entry: &str, format: MessageFormat) -> Result<Program, CliError> {
+    let elements = parse_source(path, entry, format)?;
+    assemble_program(elements, entry).map_err(|e| CliError::Assemble(path.to_path_buf(), e))
+}
+
+fn read_object(path: &Path) -> Result<Object, CliError> {
+    let bytes = fs::read(path).map_err(|e| CliError::Read(path.to_path_buf(), e))?;
+    Object::from_bytes(&bytes).map_err(|e| CliError::Load(path.to_path_buf(), e))
+}
+
+/// Assembles a source file into an object, or reads an object file
+fn load_object(path: &Path, entry: &str, format: MessageFormat) -> Result<Object, CliError> {
+    if !is_source(path) {
+        return read_object(path);
+    }
+    Ok(assemble_object(parse_source(path, entry, format)?))
+}
+
+fn link_files(paths: &[PathBuf], entry: &str, format: MessageFormat) -> Result<Program, CliError> {
+    let objects = paths
+        .iter()
+        .map(|p| load_object(p, entry, format))
+        .collect::<Result<Vec<_>, _>>()?;
+    link(&objects, entry).map_err(|e| CliError::Link(paths.to_vec(), e))
+}
+
+/// Assembles a single source file, loads a single bytecode file or links multiple files
+fn load_program(
+    paths: &[PathBuf],
+    entry: &str,
+    format: MessageFormat,
+) -> Result<Program, CliError> {
+    match paths {
+        [path] if is_source(path) => assemble_source(path, entry, format),
+        [path] if !is_object(path) => read_bytecode(path),
+        _ => link_files(paths, entry, format),
+    }
+}
+
+fn is_source(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == SOURCE_EXTENSION)
+}
+
+fn is_object(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == OBJECT_EXTENSION)
+}
+
+/// Maps the value passed to `exit` to a process exit code, keeping the low byte like `exit(3)`
+fn exit_code(value: i64) -> ExitCode {
+    ExitCode::from(value as u8)
+}
+
+fn run(command: Command, format: MessageFormat) -> Result<ExitCode, CliError> {
+    match command {
+        Command::Assemble {
+            input,
+            output,
+            entry,
+            object,
+        } => {
+            let (bytes, extension) = if object {
+                (
+                    load_object(&input, &entry, format)?.to_bytes(),
+                    OBJECT_EXTENSION,
+                )
+            } else {
+                (
+                    assemble_source(&input, &entry, format)?.to_bytes(),
+                    BYTECODE_EXTENSION,
+                )
+            };
+            let output = output.unwrap_or_else(|| input.with_extension(extension));
+            fs::write(&output, bytes).map_err(|e| CliError::Write(output, e))?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Link {
+            inputs,
+            output,
+            entry,
+        } => {
+            let bytecode = link_files(&inputs, &entry, format)?.to_bytes();
+            let output = output.unwrap_or_else(|| inputs[0].with_extension(BYTECODE_EXTENSION));
+            fs::write(&output, bytecode).map_err(|e| CliError::Write(output, e))?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Run {
+            inputs,
+            entry,
+            dump_format,
+            dump_memory,
+            errors,
+            checked,
+            stack_size,
+        } => {
+            let program = load_program(&inputs, &entry, format)?;
+            let config = Config {
+                dump: DumpConfig {
+                    format: dump_format.into(),
+                    memory: dump_memory,
+                },
+                errors: errors.into(),
+                overflow: if checked {
+                    OverflowMode::Checked
+                } else {
+                    OverflowMode::Wrapping
+                },
+                stack_size,
+            };
+            let mut vm = Vm::with_config(&program, Box::new(StdIo::default()), config);
+            match vm.run() {
+                StopReason::Exited(value) => Ok(exit_code(value)),
+                StopReason::Halted => {
+                    eprintln!("execution halted");
+                    Ok(ExitCode::SUCCESS)
+                }
+                StopReason::Fault(e) => Err(CliError::Runtime(inputs, Box::new(e))),
+                StopReason::BudgetExhausted => unreachable!("the program runs without a budget"),
+            }
+        }
+        Command::Check { inputs, entry } => {
+            load_program(&inputs, &entry, format)?;
+            for input in &inputs {
+                println!("{}: no errors found", input.display());
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Disassemble { input, output } => {
+            let program = read_bytecode(&input)?;
+            let source = disassemble_to_source(&program)
+                .map_err(|e| CliError::Disassemble(input.clone(), e))?;
+            match output {
+                Some(output) => {
+                    fs::write(&output, source).map_err(|e| CliError::Write(output, e))?
+                }
+                None => print!("{}", source),
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command, cli.message_format) {
+        Ok(code) => code,
+        Err(e) => {
+            report(&e, cli.message_format);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edu_asm_parser::parse_all;
+
+    use super::*;
+
+    #[test]
+    fn parse_errors_point_into_the_source() {
+        let text = "main:\n    addts $G_9 1 2\n    nope\n";
+        let error = CliError::Parse(PathBuf::from("main.edu"), parse_all(text).errors);
+
+        let rendered: Vec<_> = diagnostics(&error)
+            .into_iter()
+            .map(|(d, path)| d.render(path.map(|path| Source { path, text }), false))
+            .collect();
+
+        assert_eq!(
+            rendered,
+            [
+                "error: parsing register failed: the register string `$G_9` is invalid
+ --> main.edu:2:11
+  |
+2 |     addts $G_9 1 2
+  |           ^^^^ not a register
+  = help: the general purpose registers are `$G_0` to `$G_7`",
+                "error: instruction `nope` is unknown
+ --> main.edu:3:5
+  |
+3 |     nope
+  |     ^^^^ unknown instruction
+  = help: did you mean `nop`?"
+            ]
+        );
+    }
+
+    #[test]
+    fn check_bytecode() {
+        let (parsed, _) = edu_asm_parser::parse("_start:\n\texit 0\n").unwrap();
+        let program = assemble_program(parsed, DEFAULT_ENTRY).unwrap();
+        let path = std::env::temp_dir().join(format!("edu-asm-check-{}.bin", std::process::id()));
+        fs::write(&path, program.to_bytes()).unwrap();
+
+        let check = |path: &Path| Command::Check {
+            inputs: vec![path.to_path_buf()],
+            entry: DEFAULT_ENTRY.to_string(),
+        };
+        let checked = run(check(&path), MessageFormat::Plain);
+        fs::write(&path, b"not bytecode").unwrap();
+        let invalid = run(check(&path), MessageFormat::Plain);
+        fs::remove_file(&path).unwrap();
+
+        assert!(checked.is_ok());
+        assert!(matches!(
+            invalid,
+            Err(CliError::Load(_, ProgramError::InvalidMagic))
+        ));
+    }
+
+    #[test]
+    fn source_detection() {
+        assert!(is_source(Path::new("examples/basic.edu")));
+        assert!(!is_source(Path::new("examples/basic.bin")));
+        assert!(is_object(Path::new("examples/basic.obj")));
+    }
+
+    #[test]
+    fn link_error_names_files() {
+        let paths = [PathBuf::from("main.edu"), PathBuf::from("runtime.obj")];
+
+        assert_eq!(
+            render_link_error(
+                &paths,
+                &AssembleError::DuplicateSymbol("print".to_string(), 0, 1)
+            ),
+            "label `print` is defined in `main.edu` and `runtime.obj`"
+        );
+        assert_eq!(
+            render_link_error(
+                &paths,
+                &AssembleError::UndefinedSymbol(
+                    Relocation {
+                        offset: 4,
+                        symbol: "exit".to_string(),
+                        line: 2,
+                        column: 8
+                    },
+                    1
+                )
+            ),
+            "label `exit` is used at `runtime.obj:3:9`, but never defined"
+        );
+    }
+}
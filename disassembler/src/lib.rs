@@ -0,0 +1,322 @@
+use std::{collections::BTreeMap, rc::Rc, str::FromStr};
+
+use edu_asm_assembler::{
+    decode::{decode_instruction, DecodeError},
+    program::Program,
+    DEFAULT_ENTRY,
+};
+use edu_asm_parser::{
+    directive::{Directive, Section},
+    instruction::Instruction,
+    label::{LabelToken, LocAwLabel},
+    literal::LiteralToken,
+    span::{InstructionSpans, Span},
+    PureElement,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DisassembleError {
+    #[error("decoding the instruction at byte `{1}` failed: `{0}`")]
+    DecodeError(DecodeError, usize),
+    #[error("the instruction at byte `{1}` targets byte `{0}`, which is not the start of an instruction")]
+    InvalidTarget(usize, usize),
+    #[error("the entry point `{0}` is not the start of an instruction")]
+    InvalidEntry(u64),
+}
+
+/// Generates the name of the `index`th recovered label (`la`, `lb`, ..., `lz`, `lba`, ...)
+fn label_name(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut rest = index;
+    loop {
+        letters.push(b'a' + (rest % 26) as u8);
+        rest /= 26;
+        if rest == 0 {
+            break;
+        }
+    }
+    letters.push(b'l');
+    letters.reverse();
+    String::from_utf8(letters).unwrap()
+}
+
+fn decode_all(program: &[u8]) -> Result<Vec<(usize, Instruction)>, DisassembleError> {
+    let mut ret = Vec::new();
+    let mut pos = 0;
+    while pos < program.len() {
+        let (instruction, len) = decode_instruction(&program[pos..])
+            .map_err(|e| DisassembleError::DecodeError(e, pos))?;
+        ret.push((pos, instruction));
+        pos += len;
+    }
+    Ok(ret)
+}
+
+/// Returns the name of a symbol at `loc`, that can be used as a global label in source
+fn symbol_name(program: &Program, loc: usize) -> Option<&str> {
+    program
+        .symbols
+        .iter()
+        .filter(|s| s.address == loc as u64 && s.name != DEFAULT_ENTRY)
+        .map(|s| s.name.as_str())
+        .find(|name| {
+            // linked objects can define local labels with the same name
+            let unique = program.symbols.iter().filter(|s| s.name == *name).count() == 1;
+            unique
+                && LabelToken::from_str(&format!("{}:", name))
+                    .is_ok_and(|l| !l.is_local() && !l.is_numeric())
+        })
+}
+
+/// Number of bytes per `.byte` directive in the recovered data section
+const BYTES_PER_DIRECTIVE: usize = 16;
+
+/// Turns the assembled `program` back into a stream of instructions, labels and directives.
+///
+/// Every target of a jump or call and every label address moved into a register gets a generated
+/// label, named after the symbol at its location if there is one. The entry point is labeled
+/// [`DEFAULT_ENTRY`]. The `loc` of the labels is the byte offset they point to. The data section
+/// is recovered as `.byte` directives.
+pub fn disassemble(program: &Program) -> Result<Vec<PureElement>, DisassembleError> {
+    let code = &program.code;
+    let mut instructions = decode_all(code)?;
+    let end = code.len() + program.data.len();
+
+    let mut targets: BTreeMap<usize, usize> = BTreeMap::new();
+    for (offset, instruction) in instructions.iter() {
+        if let Some(label) = instruction.get_label() {
+            let loc = label.label.as_ref().unwrap().loc;
+            targets.insert(loc, *offset);
+        }
+    }
+    let entry = usize::try_from(program.entry)
+        .ok()
+        .filter(|e| instructions.binary_search_by_key(e, |(o, _)| *o).is_ok())
+        .ok_or(DisassembleError::InvalidEntry(program.entry))?;
+    targets.entry(entry).or_insert(entry);
+
+    let mut labels: BTreeMap<usize, Rc<LocAwLabel>> = BTreeMap::new();
+    let mut generated = (0..)
+        .map(label_name)
+        .filter(|n| program.symbols.iter().all(|s| &s.name != n));
+    for (loc, source) in targets {
+        let is_start = loc >= code.len() && loc <= end
+            || instructions
+                .binary_search_by_key(&loc, |(offset, _)| *offset)
+                .is_ok();
+        if !is_start {
+            return Err(DisassembleError::InvalidTarget(loc, source));
+        }
+        let name = if loc == entry {
+            DEFAULT_ENTRY.to_string()
+        } else if let Some(symbol) = symbol_name(program, loc) {
+            symbol.to_string()
+        } else {
+            generated.next().unwrap()
+        };
+        labels.insert(loc, Rc::new(LocAwLabel { name, loc }));
+    }
+
+    for (_, instruction) in instructions.iter_mut() {
+        if let Some(label_ref) = instruction.get_label_mut() {
+            let loc = label_ref.label.as_ref().unwrap().loc;
+            let label = labels.get(&loc).cloned().unwrap();
+            label_ref.content = label.name.clone();
+            label_ref.label = Some(label);
+        }
+    }
+
+    let mut ret = Vec::with_capacity(instructions.len() + labels.len());
+    for (offset, instruction) in instructions {
+        if let Some(label) = labels.get(&offset) {
+            ret.push(PureElement::Label(label.clone(), Span::default()));
+        }
+        ret.push(PureElement::Instruction(
+            instruction,
+            InstructionSpans::default(),
+        ));
+    }
+
+    let data_labels = labels.range(code.len()..);
+    if program.data.is_empty() {
+        ret.extend(data_labels.map(|(_, l)| PureElement::Label(l.clone(), Span::default())));
+        return Ok(ret);
+    }
+    ret.push(PureElement::Directive(
+        Directive::Section(Section::Data),
+        Span::default(),
+    ));
+    let mut bounds: Vec<usize> = data_labels.map(|(loc, _)| *loc).collect();
+    bounds.push(end);
+    let mut pos = code.len();
+    for bound in bounds {
+        while pos < bound {
+            let chunk_end = bound.min(pos + BYTES_PER_DIRECTIVE);
+            let values = program.data[pos - code.len()..chunk_end - code.len()]
+                .iter()
+                .map(|b| LiteralToken::Unsigned((*b).into()))
+                .collect();
+            ret.push(PureElement::Directive(
+                Directive::Byte(values),
+                Span::default(),
+            ));
+            pos = chunk_end;
+        }
+        if let Some(label) = labels.get(&bound) {
+            ret.push(PureElement::Label(label.clone(), Span::default()));
+        }
+    }
+
+    Ok(ret)
+}
+
+/// Renders a stream of instructions and labels as edu asm source
+pub fn format_elements(elements: &[PureElement]) -> String {
+    let mut ret = String::new();
+    for element in elements {
+        match element {
+            PureElement::Label(l, _) => {
+                ret.push_str(&l.name);
+                ret.push_str(":\n");
+            }
+            PureElement::Instruction(i, _) => {
+                ret.push('\t');
+                ret.push_str(&i.to_string());
+                ret.push('\n');
+            }
+            PureElement::Directive(d, _) => {
+                if d.is_data() {
+                    ret.push('\t');
+                }
+                ret.push_str(&d.to_string());
+                ret.push('\n');
+            }
+        }
+    }
+    ret
+}
+
+/// Disassembles `program` into edu asm source, that can be parsed and assembled again
+pub fn disassemble_to_source(program: &Program) -> Result<String, DisassembleError> {
+    disassemble(program).map(|e| format_elements(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use edu_asm_assembler::assemble_program;
+    use edu_asm_parser::parse;
+
+    use super::*;
+
+    fn assemble(program: &str) -> Program {
+        let (parsed, _) = parse(program).unwrap();
+        assemble_program(parsed, DEFAULT_ENTRY).unwrap()
+    }
+
+    fn round_trip(program: &str) {
+        let assembled = assemble(program);
+
+        let source = disassemble_to_source(&assembled).unwrap();
+        let reassembled = assemble(&source);
+
+        // only referenced labels are recovered, so the symbol tables can differ
+        assert_eq!(
+            (assembled.code, assembled.data, assembled.entry),
+            (reassembled.code, reassembled.data, reassembled.entry),
+            "disassembled source:\n{}",
+            source
+        );
+    }
+
+    #[test]
+    fn round_trip_example() {
+        round_trip(include_str!("../../examples/basic.edu"));
+    }
+
+    #[test]
+    fn round_trip_all_groups() {
+        const PROGRAM: &str = r#"
+        _start:
+            addts $G_0 $Z -10
+            addis $G_0 9223372036854775808u
+            addtu $G_1 $G_0 10u
+            subiu $G_1 $G_0
+            lshlt $G_2 $G_1 3
+            ashri $G_2 1
+            andt $G_3 $G_2 255
+            nott $G_4 $G_3
+            noi $G_4
+            mults_e $G_5 $G_4 -3
+            divtu_e $G_5 $G_6 $G_5 7
+        loop:
+            jmpgts $G_0 $G_1 :loop
+            jmplts $G_0 -5 :loop
+            jmpeq 3u $G_1 :loop
+            cal :function
+            mov $G_7 $S_E
+            mov $G_6 :function
+            calr $G_6
+            jmpr $G_6
+            loado $G_7 $G_7 8
+            storbo $G_7 $S_B 16
+            push $G_7
+            pop $R
+            print 65
+            read $G_0
+            dump
+            nop
+            exit $G_0
+        function:
+            ret $G_1
+        end:
+            jmp :end
+        "#;
+
+        round_trip(PROGRAM);
+    }
+
+    #[test]
+    fn round_trip_data() {
+        round_trip(
+            "helper:
+                ret $Z
+            _start:
+                mov $G_0 :text
+                mov $G_1 :number
+                cal :helper
+                exit 0
+            .data
+            text:
+                .asciz \"a longer text, that needs multiple lines\"
+            number:
+                .align 8
+                .word -1
+            end:
+            ",
+        );
+    }
+
+    #[test]
+    fn recovers_labels() {
+        let source = "loop:\n\tnop\n_start:\n\tjmp :loop\n";
+        let assembled = assemble(source);
+        let stripped = Program {
+            symbols: Vec::new(),
+            ..assembled.clone()
+        };
+
+        assert_eq!(disassemble_to_source(&assembled).unwrap(), source);
+        assert_eq!(
+            disassemble_to_source(&stripped).unwrap(),
+            "la:\n\tnop\n_start:\n\tjmp :la\n"
+        );
+    }
+
+    #[test]
+    fn label_names() {
+        assert_eq!(label_name(0), "la");
+        assert_eq!(label_name(25), "lz");
+        assert_eq!(label_name(26), "lba");
+    }
+}
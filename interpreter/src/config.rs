@@ -0,0 +1,60 @@
+use crate::memory::DEFAULT_STACK_SIZE;
+
+/// Format of the state snapshot written by `dump`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DumpFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// A single JSON object per `dump`
+    Json,
+}
+
+/// Configures the output of `dump`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DumpConfig {
+    pub format: DumpFormat,
+    /// Whether the touched memory pages are part of the snapshot
+    pub memory: bool,
+}
+
+/// How the vm reacts to faults, that have an error code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorMode {
+    /// Stop the program
+    #[default]
+    Trap,
+    /// Store the error code in `$E` and continue with the next instruction
+    Flag,
+}
+
+/// How arithmetic instructions handle results, that don't fit into 64 bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowMode {
+    /// Wrap around in two's complement
+    #[default]
+    Wrapping,
+    /// Raise an overflow fault, which traps or sets `$E` depending on the [`ErrorMode`]
+    Checked,
+}
+
+/// Configures the behaviour of a [`crate::Vm`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub dump: DumpConfig,
+    pub errors: ErrorMode,
+    pub overflow: OverflowMode,
+    /// Maximum size of the stack in bytes, pushing beyond it raises a stack overflow fault
+    pub stack_size: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            dump: DumpConfig::default(),
+            errors: ErrorMode::default(),
+            overflow: OverflowMode::default(),
+            stack_size: DEFAULT_STACK_SIZE,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use edu_asm_assembler::{decode::DecodeError, program::DebugLine};
+use edu_asm_parser::{
+    instruction::{ArithmeticMultDivEasy, Instruction, Memory},
+    register::RegisterToken,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum RuntimeErrorKind {
+    #[error("arithmetic overflow")]
+    Overflow,
+    #[error("the stack is empty")]
+    StackUnderflow,
+    #[error("the stack exceeds its maximum size")]
+    StackOverflow,
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("the instruction pointer `{0:#x}` is outside of the code section")]
+    PcOutOfRange(u64),
+    #[error("memory access at `{0:#x}` is out of bounds")]
+    InvalidMemoryAccess(u64),
+    #[error("register `{0}` can't be used")]
+    InvalidRegister(RegisterToken),
+    #[error("instruction `{0}` is unimplemented")]
+    UnimplementedInstruction(String),
+    #[error("decoding the instruction failed: `{0}`")]
+    InvalidInstruction(DecodeError),
+    #[error("input or output failed: `{0}`")]
+    Io(String),
+}
+
+impl RuntimeErrorKind {
+    /// The value stored in `$E` for this fault, `None` if the program can't continue after it
+    pub fn error_code(&self) -> Option<u64> {
+        match self {
+            RuntimeErrorKind::Overflow => Some(1),
+            RuntimeErrorKind::DivisionByZero => Some(2),
+            RuntimeErrorKind::StackUnderflow => Some(3),
+            RuntimeErrorKind::InvalidMemoryAccess(_) => Some(4),
+            RuntimeErrorKind::StackOverflow => Some(5),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the operand of `instruction` causing this fault, if it is caused by one
+    pub fn operand(&self, instruction: &Instruction) -> Option<usize> {
+        match (self, instruction) {
+            (
+                RuntimeErrorKind::DivisionByZero,
+                Instruction::ArithmeticMultDivEasy(
+                    ArithmeticMultDivEasy::DivTsE { .. } | ArithmeticMultDivEasy::DivTuE { .. },
+                ),
+            ) => Some(3),
+            (
+                RuntimeErrorKind::InvalidMemoryAccess(_),
+                Instruction::Memory(
+                    Memory::Load { .. }
+                    | Memory::LoadO { .. }
+                    | Memory::Loadb { .. }
+                    | Memory::LoadbO { .. }
+                    | Memory::Stor { .. }
+                    | Memory::StorO { .. }
+                    | Memory::Storb { .. }
+                    | Memory::StorbO { .. },
+                ),
+            ) => Some(1),
+            (RuntimeErrorKind::InvalidRegister(register), _) => {
+                let register = register.to_string();
+                let line = instruction.to_string();
+                line.split_whitespace().skip(1).position(|o| o == register)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A fault that stopped the execution of a program.
+///
+/// `index` is the byte offset of the faulting instruction, `line` contains the instruction as
+/// source, if it could be decoded. `operand` is the index of the operand causing the fault, see
+/// [`RuntimeErrorKind::operand`]. `location` is the position of the instruction in the source
+/// files, if the program has debug lines.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("{kind} at byte `{index}`{}", .line.as_ref().map(|l| format!(" (`{}`)", l)).unwrap_or_default())]
+pub struct RuntimeError {
+    pub kind: RuntimeErrorKind,
+    pub index: usize,
+    pub line: Option<String>,
+    pub operand: Option<usize>,
+    pub location: Option<DebugLine>,
+}
@@ -2,10 +2,11 @@ use edu_asm_parser::instruction::ArithmeticBase;
 
 use crate::{
     behaviour::{Readable, Writeable},
+    error::RuntimeErrorKind,
     register::RegisterSpecifier,
 };
 
-use super::{Executable, RegOrLit};
+use super::{apply_overflow, Executable, RegOrLit};
 
 pub(crate) struct AddTs<D: Writeable, S: Readable, T: Readable> {
     d: D,
@@ -14,11 +15,12 @@ pub(crate) struct AddTs<D: Writeable, S: Readable, T: Readable> {
 }
 
 impl<D: Writeable, S: Readable, T: Readable> Executable for AddTs<D, S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_signed(state);
         let t_val = self.t.get_signed(state);
-        let d_val = s_val + t_val;
+        let d_val = apply_overflow(state, s_val.overflowing_add(t_val))?;
         self.d.set_signed(state, d_val);
+        Ok(())
     }
 }
 
@@ -28,11 +30,12 @@ pub(crate) struct AddIs<S: Writeable + Readable, T: Readable> {
 }
 
 impl<S: Writeable + Readable, T: Readable> Executable for AddIs<S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_signed(state);
         let t_val = self.t.get_signed(state);
-        let tmp = s_val + t_val;
+        let tmp = apply_overflow(state, s_val.overflowing_add(t_val))?;
         self.s.set_signed(state, tmp);
+        Ok(())
     }
 }
 
@@ -43,11 +46,12 @@ pub(crate) struct AddTu<D: Writeable, S: Readable, T: Readable> {
 }
 
 impl<D: Writeable, S: Readable, T: Readable> Executable for AddTu<D, S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_unsigned(state);
         let t_val = self.t.get_unsigned(state);
-        let d_val = s_val + t_val;
+        let d_val = apply_overflow(state, s_val.overflowing_add(t_val))?;
         self.d.set_unsigned(state, d_val);
+        Ok(())
     }
 }
 
@@ -57,11 +61,12 @@ pub(crate) struct AddIu<S: Writeable + Readable, T: Readable> {
 }
 
 impl<S: Writeable + Readable, T: Readable> Executable for AddIu<S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_unsigned(state);
         let t_val = self.t.get_unsigned(state);
-        let tmp = s_val + t_val;
+        let tmp = apply_overflow(state, s_val.overflowing_add(t_val))?;
         self.s.set_unsigned(state, tmp);
+        Ok(())
     }
 }
 
@@ -72,11 +77,12 @@ pub(crate) struct SubTs<D: Writeable, S: Readable, T: Readable> {
 }
 
 impl<D: Writeable, S: Readable, T: Readable> Executable for SubTs<D, S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_signed(state);
         let t_val = self.t.get_signed(state);
-        let d_val = s_val - t_val;
+        let d_val = apply_overflow(state, s_val.overflowing_sub(t_val))?;
         self.d.set_signed(state, d_val);
+        Ok(())
     }
 }
 
@@ -86,11 +92,12 @@ pub(crate) struct SubIs<S: Writeable + Readable, T: Readable> {
 }
 
 impl<S: Writeable + Readable, T: Readable> Executable for SubIs<S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_signed(state);
         let t_val = self.t.get_signed(state);
-        let tmp = s_val - t_val;
+        let tmp = apply_overflow(state, s_val.overflowing_sub(t_val))?;
         self.s.set_signed(state, tmp);
+        Ok(())
     }
 }
 
@@ -101,11 +108,12 @@ pub(crate) struct SubTu<D: Writeable, S: Readable, T: Readable> {
 }
 
 impl<D: Writeable, S: Readable, T: Readable> Executable for SubTu<D, S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_unsigned(state);
         let t_val = self.t.get_unsigned(state);
-        let d_val = s_val - t_val;
+        let d_val = apply_overflow(state, s_val.overflowing_sub(t_val))?;
         self.d.set_unsigned(state, d_val);
+        Ok(())
     }
 }
 
@@ -115,21 +123,24 @@ pub(crate) struct SubIu<S: Writeable + Readable, T: Readable> {
 }
 
 impl<S: Writeable + Readable, T: Readable> Executable for SubIu<S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_unsigned(state);
         let t_val = self.t.get_unsigned(state);
-        let tmp = s_val - t_val;
+        let tmp = apply_overflow(state, s_val.overflowing_sub(t_val))?;
         self.s.set_unsigned(state, tmp);
+        Ok(())
     }
 }
 
 #[inline]
-pub(super) fn transpile_arithmetic_base(instr: ArithmeticBase) -> Box<dyn Executable> {
-    match instr {
+pub(super) fn transpile_arithmetic_base(
+    instr: ArithmeticBase,
+) -> Result<Box<dyn Executable>, RuntimeErrorKind> {
+    let ret: Box<dyn Executable> = match instr {
         ArithmeticBase::AddTs { d, s, t } => {
-            let d_spec = RegisterSpecifier::from(d);
-            let s_var: RegOrLit = s.into();
-            let t_var: RegOrLit = t.into();
+            let d_spec = RegisterSpecifier::try_from(d)?;
+            let s_var: RegOrLit = s.try_into()?;
+            let t_var: RegOrLit = t.try_into()?;
             Box::new(AddTs {
                 d: d_spec,
                 s: s_var,
@@ -137,8 +148,8 @@ pub(super) fn transpile_arithmetic_base(instr: ArithmeticBase) -> Box<dyn Execut
             })
         }
         ArithmeticBase::AddIs { s, t } => {
-            let d_spec: RegisterSpecifier = s.into();
-            let t_var: RegOrLit = t.into();
+            let d_spec: RegisterSpecifier = s.try_into()?;
+            let t_var: RegOrLit = t.try_into()?;
             Box::new(AddIs {
                 s: d_spec,
                 t: t_var,
@@ -149,20 +160,20 @@ pub(super) fn transpile_arithmetic_base(instr: ArithmeticBase) -> Box<dyn Execut
             s: s_t,
             t: t_t,
         } => {
-            let d: RegisterSpecifier = d_t.into();
-            let s: RegOrLit = s_t.into();
-            let t: RegOrLit = t_t.into();
+            let d: RegisterSpecifier = d_t.try_into()?;
+            let s: RegOrLit = s_t.try_into()?;
+            let t: RegOrLit = t_t.try_into()?;
             Box::new(AddTu { d, s, t })
         }
         ArithmeticBase::AddIu { s: s_t, t: t_t } => {
-            let s: RegisterSpecifier = s_t.into();
-            let t: RegOrLit = t_t.into();
+            let s: RegisterSpecifier = s_t.try_into()?;
+            let t: RegOrLit = t_t.try_into()?;
             Box::new(AddIu { s, t })
         }
         ArithmeticBase::SubTs { d, s, t } => {
-            let d_spec = RegisterSpecifier::from(d);
-            let s_var: RegOrLit = s.into();
-            let t_var: RegOrLit = t.into();
+            let d_spec = RegisterSpecifier::try_from(d)?;
+            let s_var: RegOrLit = s.try_into()?;
+            let t_var: RegOrLit = t.try_into()?;
             Box::new(SubTs {
                 d: d_spec,
                 s: s_var,
@@ -170,8 +181,8 @@ pub(super) fn transpile_arithmetic_base(instr: ArithmeticBase) -> Box<dyn Execut
             })
         }
         ArithmeticBase::SubIs { s, t } => {
-            let d_spec: RegisterSpecifier = s.into();
-            let t_var: RegOrLit = t.into();
+            let d_spec: RegisterSpecifier = s.try_into()?;
+            let t_var: RegOrLit = t.try_into()?;
             Box::new(SubIs {
                 s: d_spec,
                 t: t_var,
@@ -182,15 +193,16 @@ pub(super) fn transpile_arithmetic_base(instr: ArithmeticBase) -> Box<dyn Execut
             s: s_t,
             t: t_t,
         } => {
-            let d: RegisterSpecifier = d_t.into();
-            let s: RegOrLit = s_t.into();
-            let t: RegOrLit = t_t.into();
+            let d: RegisterSpecifier = d_t.try_into()?;
+            let s: RegOrLit = s_t.try_into()?;
+            let t: RegOrLit = t_t.try_into()?;
             Box::new(SubTu { d, s, t })
         }
         ArithmeticBase::SubIu { s: s_t, t: t_t } => {
-            let s: RegisterSpecifier = s_t.into();
-            let t: RegOrLit = t_t.into();
+            let s: RegisterSpecifier = s_t.try_into()?;
+            let t: RegOrLit = t_t.try_into()?;
             Box::new(SubIu { s, t })
         }
-    }
+    };
+    Ok(ret)
 }
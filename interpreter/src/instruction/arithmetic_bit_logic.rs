@@ -0,0 +1,178 @@
+use edu_asm_parser::instruction::ArithmeticBitLogic;
+
+use crate::{
+    behaviour::{Readable, Writeable},
+    error::RuntimeErrorKind,
+    register::RegisterSpecifier,
+};
+
+use super::{Executable, RegOrLit};
+
+pub(crate) struct AndT<D: Writeable, S: Readable, T: Readable> {
+    d: D,
+    s: S,
+    t: T,
+}
+
+impl<D: Writeable, S: Readable, T: Readable> Executable for AndT<D, S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let d_val = s_val & t_val;
+        self.d.set_unsigned(state, d_val);
+        Ok(())
+    }
+}
+
+pub(crate) struct AndI<S: Writeable + Readable, T: Readable> {
+    s: S,
+    t: T,
+}
+
+impl<S: Writeable + Readable, T: Readable> Executable for AndI<S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let tmp = s_val & t_val;
+        self.s.set_unsigned(state, tmp);
+        Ok(())
+    }
+}
+
+pub(crate) struct OrT<D: Writeable, S: Readable, T: Readable> {
+    d: D,
+    s: S,
+    t: T,
+}
+
+impl<D: Writeable, S: Readable, T: Readable> Executable for OrT<D, S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let d_val = s_val | t_val;
+        self.d.set_unsigned(state, d_val);
+        Ok(())
+    }
+}
+
+pub(crate) struct OrI<S: Writeable + Readable, T: Readable> {
+    s: S,
+    t: T,
+}
+
+impl<S: Writeable + Readable, T: Readable> Executable for OrI<S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let tmp = s_val | t_val;
+        self.s.set_unsigned(state, tmp);
+        Ok(())
+    }
+}
+
+pub(crate) struct XorT<D: Writeable, S: Readable, T: Readable> {
+    d: D,
+    s: S,
+    t: T,
+}
+
+impl<D: Writeable, S: Readable, T: Readable> Executable for XorT<D, S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let d_val = s_val ^ t_val;
+        self.d.set_unsigned(state, d_val);
+        Ok(())
+    }
+}
+
+pub(crate) struct XorI<S: Writeable + Readable, T: Readable> {
+    s: S,
+    t: T,
+}
+
+impl<S: Writeable + Readable, T: Readable> Executable for XorI<S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let tmp = s_val ^ t_val;
+        self.s.set_unsigned(state, tmp);
+        Ok(())
+    }
+}
+
+pub(crate) struct NotT<D: Writeable, S: Readable> {
+    d: D,
+    s: S,
+}
+
+impl<D: Writeable, S: Readable> Executable for NotT<D, S> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        self.d.set_unsigned(state, !s_val);
+        Ok(())
+    }
+}
+
+pub(crate) struct NoI<S: Writeable + Readable> {
+    s: S,
+}
+
+impl<S: Writeable + Readable> Executable for NoI<S> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        self.s.set_unsigned(state, !s_val);
+        Ok(())
+    }
+}
+
+#[inline]
+pub(super) fn transpile_arithmetic_bit_logic(
+    instr: ArithmeticBitLogic,
+) -> Result<Box<dyn Executable>, RuntimeErrorKind> {
+    let ret: Box<dyn Executable> = match instr {
+        ArithmeticBitLogic::AndT { d, s, t } => {
+            let d = RegisterSpecifier::try_from(d)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(AndT { d, s, t })
+        }
+        ArithmeticBitLogic::AndI { s, t } => {
+            let s = RegisterSpecifier::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(AndI { s, t })
+        }
+        ArithmeticBitLogic::OrT { d, s, t } => {
+            let d = RegisterSpecifier::try_from(d)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(OrT { d, s, t })
+        }
+        ArithmeticBitLogic::OrI { s, t } => {
+            let s = RegisterSpecifier::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(OrI { s, t })
+        }
+        ArithmeticBitLogic::XorT { d, s, t } => {
+            let d = RegisterSpecifier::try_from(d)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(XorT { d, s, t })
+        }
+        ArithmeticBitLogic::XorI { s, t } => {
+            let s = RegisterSpecifier::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(XorI { s, t })
+        }
+        ArithmeticBitLogic::NotT { d, s } => {
+            let d = RegisterSpecifier::try_from(d)?;
+            let s = RegOrLit::try_from(s)?;
+            Box::new(NotT { d, s })
+        }
+        ArithmeticBitLogic::NoI { s } => {
+            let s = RegisterSpecifier::try_from(s)?;
+            Box::new(NoI { s })
+        }
+    };
+    Ok(ret)
+}
@@ -2,10 +2,11 @@ use edu_asm_parser::instruction::ArithmeticMultDivEasy;
 
 use crate::{
     behaviour::{Readable, Writeable},
+    error::RuntimeErrorKind,
     register::RegisterSpecifier,
 };
 
-use super::{Executable, RegOrLit};
+use super::{apply_overflow, Executable, RegOrLit};
 
 pub(crate) struct MulTsE<D: Writeable, S: Readable, T: Readable> {
     d: D,
@@ -14,11 +15,12 @@ pub(crate) struct MulTsE<D: Writeable, S: Readable, T: Readable> {
 }
 
 impl<D: Writeable, S: Readable, T: Readable> Executable for MulTsE<D, S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_signed(state);
         let t_val = self.t.get_signed(state);
-        let d_val = s_val * t_val;
+        let d_val = apply_overflow(state, s_val.overflowing_mul(t_val))?;
         self.d.set_signed(state, d_val);
+        Ok(())
     }
 }
 
@@ -28,11 +30,12 @@ pub(crate) struct MulIsE<S: Writeable + Readable, T: Readable> {
 }
 
 impl<S: Writeable + Readable, T: Readable> Executable for MulIsE<S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_signed(state);
         let t_val = self.t.get_signed(state);
-        let tmp = s_val * t_val;
+        let tmp = apply_overflow(state, s_val.overflowing_mul(t_val))?;
         self.s.set_signed(state, tmp);
+        Ok(())
     }
 }
 
@@ -43,11 +46,12 @@ pub(crate) struct MulTuE<D: Writeable, S: Readable, T: Readable> {
 }
 
 impl<D: Writeable, S: Readable, T: Readable> Executable for MulTuE<D, S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_unsigned(state);
         let t_val = self.t.get_unsigned(state);
-        let d_val = s_val * t_val;
+        let d_val = apply_overflow(state, s_val.overflowing_mul(t_val))?;
         self.d.set_unsigned(state, d_val);
+        Ok(())
     }
 }
 
@@ -57,11 +61,12 @@ pub(crate) struct MulIuE<S: Writeable + Readable, T: Readable> {
 }
 
 impl<S: Writeable + Readable, T: Readable> Executable for MulIuE<S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_unsigned(state);
         let t_val = self.t.get_unsigned(state);
-        let tmp = s_val * t_val;
+        let tmp = apply_overflow(state, s_val.overflowing_mul(t_val))?;
         self.s.set_unsigned(state, tmp);
+        Ok(())
     }
 }
 
@@ -73,13 +78,17 @@ pub(crate) struct DivTsE<D: Writeable, R: Writeable, S: Readable, T: Readable> {
 }
 
 impl<D: Writeable, R: Writeable, S: Readable, T: Readable> Executable for DivTsE<D, R, S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_signed(state);
         let t_val = self.t.get_signed(state);
-        let r_val = s_val % t_val;
-        let d_val = s_val / t_val;
+        if t_val == 0 {
+            return Err(RuntimeErrorKind::DivisionByZero);
+        }
+        let r_val = apply_overflow(state, s_val.overflowing_rem(t_val))?;
+        let d_val = apply_overflow(state, s_val.overflowing_div(t_val))?;
         self.r.set_signed(state, r_val);
         self.d.set_signed(state, d_val);
+        Ok(())
     }
 }
 
@@ -91,54 +100,61 @@ pub(crate) struct DivTuE<D: Writeable, R: Writeable, S: Readable, T: Readable> {
 }
 
 impl<D: Writeable, R: Writeable, S: Readable, T: Readable> Executable for DivTuE<D, R, S, T> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let s_val = self.s.get_unsigned(state);
         let t_val = self.t.get_unsigned(state);
+        if t_val == 0 {
+            return Err(RuntimeErrorKind::DivisionByZero);
+        }
         let r_val = s_val % t_val;
         let d_val = s_val / t_val;
         self.r.set_unsigned(state, r_val);
         self.d.set_unsigned(state, d_val);
+        Ok(())
     }
 }
 
 #[inline]
-pub(super) fn transpile_arithmetic_mult_div(instr: ArithmeticMultDivEasy) -> Box<dyn Executable> {
-    match instr {
+pub(super) fn transpile_arithmetic_mult_div(
+    instr: ArithmeticMultDivEasy,
+) -> Result<Box<dyn Executable>, RuntimeErrorKind> {
+    let ret: Box<dyn Executable> = match instr {
         ArithmeticMultDivEasy::MulTsE { d, s, t } => {
-            let d = RegisterSpecifier::from(d);
-            let s = RegOrLit::from(s);
-            let t = RegOrLit::from(t);
+            let d = RegisterSpecifier::try_from(d)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
             Box::new(MulTsE { d, s, t })
         }
         ArithmeticMultDivEasy::MulIsE { s, t } => {
-            let s = RegisterSpecifier::from(s);
-            let t = RegOrLit::from(t);
+            let s = RegisterSpecifier::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
             Box::new(MulIsE { s, t })
         }
         ArithmeticMultDivEasy::MulTuE { d, s, t } => {
-            let d = RegisterSpecifier::from(d);
-            let s = RegOrLit::from(s);
-            let t = RegOrLit::from(t);
+            let d = RegisterSpecifier::try_from(d)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
             Box::new(MulTuE { d, s, t })
         }
         ArithmeticMultDivEasy::MulIuE { s, t } => {
-            let s = RegisterSpecifier::from(s);
-            let t = RegOrLit::from(t);
+            let s = RegisterSpecifier::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
             Box::new(MulIuE { s, t })
         }
         ArithmeticMultDivEasy::DivTsE { d, r, s, t } => {
-            let d = RegisterSpecifier::from(d);
-            let r = RegisterSpecifier::from(r);
-            let s = RegOrLit::from(s);
-            let t = RegOrLit::from(t);
+            let d = RegisterSpecifier::try_from(d)?;
+            let r = RegisterSpecifier::try_from(r)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
             Box::new(DivTsE { d, r, s, t })
         }
         ArithmeticMultDivEasy::DivTuE { d, r, s, t } => {
-            let d = RegisterSpecifier::from(d);
-            let r = RegisterSpecifier::from(r);
-            let s = RegOrLit::from(s);
-            let t = RegOrLit::from(t);
+            let d = RegisterSpecifier::try_from(d)?;
+            let r = RegisterSpecifier::try_from(r)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
             Box::new(DivTuE { d, r, s, t })
         }
-    }
+    };
+    Ok(ret)
 }
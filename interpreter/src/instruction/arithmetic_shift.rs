@@ -0,0 +1,193 @@
+use edu_asm_parser::instruction::ArithmeticShift;
+
+use crate::{
+    behaviour::{Readable, Writeable},
+    error::RuntimeErrorKind,
+    register::RegisterSpecifier,
+};
+
+use super::{Executable, RegOrLit};
+
+/// Logical left shift, shifting by 64 or more bits clears the value
+#[inline]
+fn logical_left(val: u64, amount: u64) -> u64 {
+    u32::try_from(amount)
+        .ok()
+        .and_then(|a| val.checked_shl(a))
+        .unwrap_or(0)
+}
+
+/// Logical right shift, shifting by 64 or more bits clears the value
+#[inline]
+fn logical_right(val: u64, amount: u64) -> u64 {
+    u32::try_from(amount)
+        .ok()
+        .and_then(|a| val.checked_shr(a))
+        .unwrap_or(0)
+}
+
+/// Arithmetic right shift, shifting by 64 or more bits fills the value with the sign bit
+#[inline]
+fn arithmetic_right(val: u64, amount: u64) -> u64 {
+    let amount = amount.min(63) as u32;
+    ((val as i64) >> amount) as u64
+}
+
+pub(crate) struct LshLT<D: Writeable, S: Readable, T: Readable> {
+    d: D,
+    s: S,
+    t: T,
+}
+
+impl<D: Writeable, S: Readable, T: Readable> Executable for LshLT<D, S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let d_val = logical_left(s_val, t_val);
+        self.d.set_unsigned(state, d_val);
+        Ok(())
+    }
+}
+
+pub(crate) struct LshLI<S: Writeable + Readable, T: Readable> {
+    s: S,
+    t: T,
+}
+
+impl<S: Writeable + Readable, T: Readable> Executable for LshLI<S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let tmp = logical_left(s_val, t_val);
+        self.s.set_unsigned(state, tmp);
+        Ok(())
+    }
+}
+
+pub(crate) struct LshRT<D: Writeable, S: Readable, T: Readable> {
+    d: D,
+    s: S,
+    t: T,
+}
+
+impl<D: Writeable, S: Readable, T: Readable> Executable for LshRT<D, S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let d_val = logical_right(s_val, t_val);
+        self.d.set_unsigned(state, d_val);
+        Ok(())
+    }
+}
+
+pub(crate) struct LshRI<S: Writeable + Readable, T: Readable> {
+    s: S,
+    t: T,
+}
+
+impl<S: Writeable + Readable, T: Readable> Executable for LshRI<S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let tmp = logical_right(s_val, t_val);
+        self.s.set_unsigned(state, tmp);
+        Ok(())
+    }
+}
+
+pub(crate) struct AshRT<D: Writeable, S: Readable, T: Readable> {
+    d: D,
+    s: S,
+    t: T,
+}
+
+impl<D: Writeable, S: Readable, T: Readable> Executable for AshRT<D, S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let d_val = arithmetic_right(s_val, t_val);
+        self.d.set_unsigned(state, d_val);
+        Ok(())
+    }
+}
+
+pub(crate) struct AshRI<S: Writeable + Readable, T: Readable> {
+    s: S,
+    t: T,
+}
+
+impl<S: Writeable + Readable, T: Readable> Executable for AshRI<S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let s_val = self.s.get_unsigned(state);
+        let t_val = self.t.get_unsigned(state);
+        let tmp = arithmetic_right(s_val, t_val);
+        self.s.set_unsigned(state, tmp);
+        Ok(())
+    }
+}
+
+#[inline]
+pub(super) fn transpile_arithmetic_shift(
+    instr: ArithmeticShift,
+) -> Result<Box<dyn Executable>, RuntimeErrorKind> {
+    let ret: Box<dyn Executable> = match instr {
+        ArithmeticShift::LshLT { d, s, t } => {
+            let d = RegisterSpecifier::try_from(d)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(LshLT { d, s, t })
+        }
+        ArithmeticShift::LshLI { s, t } => {
+            let s = RegisterSpecifier::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(LshLI { s, t })
+        }
+        ArithmeticShift::LshRT { d, s, t } => {
+            let d = RegisterSpecifier::try_from(d)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(LshRT { d, s, t })
+        }
+        ArithmeticShift::LshRI { s, t } => {
+            let s = RegisterSpecifier::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(LshRI { s, t })
+        }
+        ArithmeticShift::AshRT { d, s, t } => {
+            let d = RegisterSpecifier::try_from(d)?;
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(AshRT { d, s, t })
+        }
+        ArithmeticShift::AshRI { s, t } => {
+            let s = RegisterSpecifier::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(AshRI { s, t })
+        }
+    };
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shift_known_values() {
+        assert_eq!(logical_left(0b1011, 4), 0b1011_0000);
+        assert_eq!(logical_left(1, 63), 0x8000_0000_0000_0000);
+        assert_eq!(logical_right(0x8000_0000_0000_0000, 63), 1);
+        assert_eq!(logical_right(0xff00, 8), 0xff);
+        assert_eq!(arithmetic_right(-256i64 as u64, 4) as i64, -16);
+        assert_eq!(arithmetic_right(256, 4), 16);
+    }
+
+    #[test]
+    fn shift_by_64_or_more() {
+        assert_eq!(logical_left(u64::MAX, 64), 0);
+        assert_eq!(logical_right(u64::MAX, 64), 0);
+        assert_eq!(logical_left(1, u64::MAX), 0);
+        assert_eq!(arithmetic_right(i64::MIN as u64, 64), u64::MAX);
+        assert_eq!(arithmetic_right(i64::MAX as u64, 1000), 0);
+    }
+}
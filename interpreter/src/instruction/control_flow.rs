@@ -2,18 +2,20 @@ use edu_asm_parser::instruction::ControlFlow;
 
 use crate::{
     behaviour::Readable,
+    error::RuntimeErrorKind,
     register::{RegisterBehaviour, RegisterSpecifier},
 };
 
-use super::Executable;
+use super::{Executable, RegOrLit};
 
 pub(crate) struct Jmp {
     loc: usize,
 }
 
 impl Executable for Jmp {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         state.registers.m.ins.jump(self.loc);
+        Ok(())
     }
 }
 
@@ -24,12 +26,13 @@ pub(crate) struct JmpEq<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpEq<L, R> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let l_val = self.l.get_unsigned(state);
         let r_val = self.r.get_unsigned(state);
         if l_val == r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -40,12 +43,13 @@ pub(crate) struct JmpNe<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpNe<L, R> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let l_val = self.l.get_unsigned(state);
         let r_val = self.r.get_unsigned(state);
         if l_val != r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -56,12 +60,13 @@ pub(crate) struct JmpGtS<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpGtS<L, R> {
-    fn execute(&self, state: &mut crate::State) {
-        let l_val = self.l.get_unsigned(state);
-        let r_val = self.r.get_unsigned(state);
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let l_val = self.l.get_signed(state);
+        let r_val = self.r.get_signed(state);
         if l_val > r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -72,12 +77,13 @@ pub(crate) struct JmpGeS<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpGeS<L, R> {
-    fn execute(&self, state: &mut crate::State) {
-        let l_val = self.l.get_unsigned(state);
-        let r_val = self.r.get_unsigned(state);
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let l_val = self.l.get_signed(state);
+        let r_val = self.r.get_signed(state);
         if l_val >= r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -88,12 +94,13 @@ pub(crate) struct JmpLtS<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpLtS<L, R> {
-    fn execute(&self, state: &mut crate::State) {
-        let l_val = self.l.get_unsigned(state);
-        let r_val = self.r.get_unsigned(state);
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let l_val = self.l.get_signed(state);
+        let r_val = self.r.get_signed(state);
         if l_val < r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -104,12 +111,13 @@ pub(crate) struct JmpLeS<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpLeS<L, R> {
-    fn execute(&self, state: &mut crate::State) {
-        let l_val = self.l.get_unsigned(state);
-        let r_val = self.r.get_unsigned(state);
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let l_val = self.l.get_signed(state);
+        let r_val = self.r.get_signed(state);
         if l_val <= r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -120,12 +128,13 @@ pub(crate) struct JmpGtU<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpGtU<L, R> {
-    fn execute(&self, state: &mut crate::State) {
-        let l_val = self.l.get_signed(state);
-        let r_val = self.r.get_signed(state);
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let l_val = self.l.get_unsigned(state);
+        let r_val = self.r.get_unsigned(state);
         if l_val > r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -136,12 +145,13 @@ pub(crate) struct JmpGeU<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpGeU<L, R> {
-    fn execute(&self, state: &mut crate::State) {
-        let l_val = self.l.get_signed(state);
-        let r_val = self.r.get_signed(state);
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let l_val = self.l.get_unsigned(state);
+        let r_val = self.r.get_unsigned(state);
         if l_val >= r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -152,12 +162,13 @@ pub(crate) struct JmpLtU<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpLtU<L, R> {
-    fn execute(&self, state: &mut crate::State) {
-        let l_val = self.l.get_signed(state);
-        let r_val = self.r.get_signed(state);
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let l_val = self.l.get_unsigned(state);
+        let r_val = self.r.get_unsigned(state);
         if l_val < r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -168,12 +179,13 @@ pub(crate) struct JmpLeU<L: Readable, R: Readable> {
 }
 
 impl<L: Readable, R: Readable> Executable for JmpLeU<L, R> {
-    fn execute(&self, state: &mut crate::State) {
-        let l_val = self.l.get_signed(state);
-        let r_val = self.r.get_signed(state);
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let l_val = self.l.get_unsigned(state);
+        let r_val = self.r.get_unsigned(state);
         if l_val <= r_val {
             state.registers.m.ins.jump(self.loc);
         }
+        Ok(())
     }
 }
 
@@ -182,10 +194,43 @@ pub(crate) struct Call {
 }
 
 impl Executable for Call {
-    fn execute(&self, state: &mut crate::State) {
-        let i_val = state.registers.m.ins.get_unsigned();
-        state.stack.push(i_val);
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let i_val = state.registers.m.ins.next() as u64;
+        state.push(i_val)?;
         state.registers.m.ins.jump(self.loc);
+        Ok(())
+    }
+}
+
+/// Converts the value of a register into a jump target
+#[inline]
+fn target(value: u64) -> Result<usize, RuntimeErrorKind> {
+    usize::try_from(value).map_err(|_| RuntimeErrorKind::PcOutOfRange(value))
+}
+
+pub(crate) struct JmpR<S: Readable> {
+    s: S,
+}
+
+impl<S: Readable> Executable for JmpR<S> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let loc = target(self.s.get_unsigned(state))?;
+        state.registers.m.ins.jump(loc);
+        Ok(())
+    }
+}
+
+pub(crate) struct CallR<S: Readable> {
+    s: S,
+}
+
+impl<S: Readable> Executable for CallR<S> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let loc = target(self.s.get_unsigned(state))?;
+        let i_val = state.registers.m.ins.next() as u64;
+        state.push(i_val)?;
+        state.registers.m.ins.jump(loc);
+        Ok(())
     }
 }
 
@@ -194,27 +239,28 @@ pub(crate) struct Ret<S: Readable> {
 }
 
 impl<S: Readable> Executable for Ret<S> {
-    fn execute(&self, state: &mut crate::State) {
-        state
-            .registers
-            .m
-            .ret
-            .set_unsigned(self.s.get_unsigned(state));
-        let target_jump_u64 = state.stack.pop().expect("stack is empty, couldn't return");
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let value = self.s.get_unsigned(state);
+        // the return address is popped first, so an empty stack leaves `$R` unchanged
+        let target_jump_u64 = state.pop()?;
+        state.registers.m.ret.set_unsigned(value);
         let target_jump =
             usize::try_from(target_jump_u64).expect("runtime archtiecture is to small");
         state.registers.m.ins.jump(target_jump);
+        Ok(())
     }
 }
 
-pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable> {
-    match instr {
+pub(super) fn transpile_control_flow(
+    instr: ControlFlow,
+) -> Result<Box<dyn Executable>, RuntimeErrorKind> {
+    let ret: Box<dyn Executable> = match instr {
         ControlFlow::Jmp { label } => Box::new(Jmp {
             loc: label.label.unwrap().loc,
         }),
         ControlFlow::JmpEq { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpEq {
                 l,
                 r,
@@ -222,8 +268,8 @@ pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable>
             })
         }
         ControlFlow::JmpNe { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpNe {
                 l,
                 r,
@@ -231,8 +277,8 @@ pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable>
             })
         }
         ControlFlow::JmpGtS { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpGtS {
                 l,
                 r,
@@ -240,8 +286,8 @@ pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable>
             })
         }
         ControlFlow::JmpGeS { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpGeS {
                 l,
                 r,
@@ -249,8 +295,8 @@ pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable>
             })
         }
         ControlFlow::JmpLtS { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpLtS {
                 l,
                 r,
@@ -258,8 +304,8 @@ pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable>
             })
         }
         ControlFlow::JmpLeS { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpLeS {
                 l,
                 r,
@@ -267,8 +313,8 @@ pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable>
             })
         }
         ControlFlow::JmpGtU { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpGtU {
                 l,
                 r,
@@ -276,8 +322,8 @@ pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable>
             })
         }
         ControlFlow::JmpGeU { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpGeU {
                 l,
                 r,
@@ -285,8 +331,8 @@ pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable>
             })
         }
         ControlFlow::JmpLtU { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpLtU {
                 l,
                 r,
@@ -294,20 +340,29 @@ pub(super) fn transpile_control_flow(instr: ControlFlow) -> Box<dyn Executable>
             })
         }
         ControlFlow::JmpLeU { l, r, label } => {
-            let l = RegisterSpecifier::from(l);
-            let r = RegisterSpecifier::from(r);
+            let l = RegOrLit::try_from(l)?;
+            let r = RegOrLit::try_from(r)?;
             Box::new(JmpLeU {
                 l,
                 r,
                 loc: label.label.unwrap().loc,
             })
         }
+        ControlFlow::JmpR { s } => {
+            let s = RegisterSpecifier::try_from(s)?;
+            Box::new(JmpR { s })
+        }
         ControlFlow::Cal { label } => Box::new(Call {
             loc: label.label.unwrap().loc,
         }),
+        ControlFlow::CalR { s } => {
+            let s = RegisterSpecifier::try_from(s)?;
+            Box::new(CallR { s })
+        }
         ControlFlow::Ret { s } => {
-            let s = RegisterSpecifier::from(s);
+            let s = RegisterSpecifier::try_from(s)?;
             Box::new(Ret { s })
         }
-    }
+    };
+    Ok(ret)
 }
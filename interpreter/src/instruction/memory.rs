@@ -1,7 +1,8 @@
-use edu_asm_parser::instruction::Memory;
+use edu_asm_parser::{instruction::Memory, literal::LiteralToken};
 
 use crate::{
     behaviour::{Readable, Writeable},
+    error::RuntimeErrorKind,
     register::RegisterSpecifier,
 };
 
@@ -13,8 +14,137 @@ pub(crate) struct Mov<T: Writeable, S: Readable> {
 }
 
 impl<T: Writeable, S: Readable> Executable for Mov<T, S> {
-    fn execute(&self, state: &mut crate::State) {
-        self.t.set_unsigned(state, self.s.get_unsigned(state))
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        self.t.set_unsigned(state, self.s.get_unsigned(state));
+        Ok(())
+    }
+}
+
+pub(crate) struct Load<T: Writeable, S: Readable> {
+    t: T,
+    s: S,
+}
+
+impl<T: Writeable, S: Readable> Executable for Load<T, S> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let address = self.s.get_unsigned(state);
+        let val = state.memory.load_word(address)?;
+        self.t.set_unsigned(state, val);
+        Ok(())
+    }
+}
+
+pub(crate) struct LoadO<T: Writeable, S: Readable, O: Readable> {
+    t: T,
+    s: S,
+    o: O,
+}
+
+impl<T: Writeable, S: Readable, O: Readable> Executable for LoadO<T, S, O> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let address = self
+            .s
+            .get_unsigned(state)
+            .wrapping_add(self.o.get_unsigned(state));
+        let val = state.memory.load_word(address)?;
+        self.t.set_unsigned(state, val);
+        Ok(())
+    }
+}
+
+pub(crate) struct Loadb<T: Writeable, S: Readable> {
+    t: T,
+    s: S,
+}
+
+impl<T: Writeable, S: Readable> Executable for Loadb<T, S> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let address = self.s.get_unsigned(state);
+        let val = state.memory.load_byte(address)? as u64;
+        self.t.set_unsigned(state, val);
+        Ok(())
+    }
+}
+
+pub(crate) struct LoadbO<T: Writeable, S: Readable, O: Readable> {
+    t: T,
+    s: S,
+    o: O,
+}
+
+impl<T: Writeable, S: Readable, O: Readable> Executable for LoadbO<T, S, O> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let address = self
+            .s
+            .get_unsigned(state)
+            .wrapping_add(self.o.get_unsigned(state));
+        let val = state.memory.load_byte(address)? as u64;
+        self.t.set_unsigned(state, val);
+        Ok(())
+    }
+}
+
+pub(crate) struct Stor<S: Readable, T: Readable> {
+    s: S,
+    t: T,
+}
+
+impl<S: Readable, T: Readable> Executable for Stor<S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let val = self.s.get_unsigned(state);
+        let address = self.t.get_unsigned(state);
+        state.memory.store_word(address, val)?;
+        Ok(())
+    }
+}
+
+pub(crate) struct StorO<S: Readable, T: Readable, O: Readable> {
+    s: S,
+    t: T,
+    o: O,
+}
+
+impl<S: Readable, T: Readable, O: Readable> Executable for StorO<S, T, O> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let val = self.s.get_unsigned(state);
+        let address = self
+            .t
+            .get_unsigned(state)
+            .wrapping_add(self.o.get_unsigned(state));
+        state.memory.store_word(address, val)?;
+        Ok(())
+    }
+}
+
+pub(crate) struct Storb<S: Readable, T: Readable> {
+    s: S,
+    t: T,
+}
+
+impl<S: Readable, T: Readable> Executable for Storb<S, T> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let val = self.s.get_unsigned(state) as u8;
+        let address = self.t.get_unsigned(state);
+        state.memory.store_byte(address, val)?;
+        Ok(())
+    }
+}
+
+pub(crate) struct StorbO<S: Readable, T: Readable, O: Readable> {
+    s: S,
+    t: T,
+    o: O,
+}
+
+impl<S: Readable, T: Readable, O: Readable> Executable for StorbO<S, T, O> {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let val = self.s.get_unsigned(state) as u8;
+        let address = self
+            .t
+            .get_unsigned(state)
+            .wrapping_add(self.o.get_unsigned(state));
+        state.memory.store_byte(address, val)?;
+        Ok(())
     }
 }
 
@@ -23,9 +153,10 @@ pub(crate) struct Push<D: Readable> {
 }
 
 impl<D: Readable> Executable for Push<D> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let val = self.d.get_unsigned(state);
-        state.stack.push(val);
+        state.push(val)?;
+        Ok(())
     }
 }
 
@@ -34,27 +165,78 @@ pub(crate) struct Pop<D: Writeable> {
 }
 
 impl<D: Writeable> Executable for Pop<D> {
-    fn execute(&self, state: &mut crate::State) {
-        let val = state.stack.pop().expect("stack is empty");
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let val = state.pop()?;
         self.d.set_unsigned(state, val);
+        Ok(())
     }
 }
 
-pub(super) fn transpile_memory(instr: Memory) -> Box<dyn Executable> {
-    match instr {
+pub(super) fn transpile_memory(instr: Memory) -> Result<Box<dyn Executable>, RuntimeErrorKind> {
+    let ret: Box<dyn Executable> = match instr {
         Memory::Mov { t, s } => {
-            let t = RegisterSpecifier::from(t);
-            let s = RegOrLit::from(s);
+            let t = RegisterSpecifier::try_from(t)?;
+            let s = RegOrLit::try_from(s)?;
+            Box::new(Mov { t, s })
+        }
+        Memory::MovL { t, label } => {
+            let t = RegisterSpecifier::try_from(t)?;
+            let loc = label.label.unwrap().loc as u64;
+            let s = RegOrLit::Literal(LiteralToken::Unsigned(loc).into());
             Box::new(Mov { t, s })
         }
+        Memory::Load { t, s } => {
+            let t = RegisterSpecifier::try_from(t)?;
+            let s = RegOrLit::try_from(s)?;
+            Box::new(Load { t, s })
+        }
+        Memory::LoadO { t, s, o } => {
+            let t = RegisterSpecifier::try_from(t)?;
+            let s = RegOrLit::try_from(s)?;
+            let o = RegOrLit::try_from(o)?;
+            Box::new(LoadO { t, s, o })
+        }
+        Memory::Loadb { t, s } => {
+            let t = RegisterSpecifier::try_from(t)?;
+            let s = RegOrLit::try_from(s)?;
+            Box::new(Loadb { t, s })
+        }
+        Memory::LoadbO { t, s, o } => {
+            let t = RegisterSpecifier::try_from(t)?;
+            let s = RegOrLit::try_from(s)?;
+            let o = RegOrLit::try_from(o)?;
+            Box::new(LoadbO { t, s, o })
+        }
+        Memory::Stor { s, t } => {
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(Stor { s, t })
+        }
+        Memory::StorO { s, t, o } => {
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            let o = RegOrLit::try_from(o)?;
+            Box::new(StorO { s, t, o })
+        }
+        Memory::Storb { s, t } => {
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            Box::new(Storb { s, t })
+        }
+        Memory::StorbO { s, t, o } => {
+            let s = RegOrLit::try_from(s)?;
+            let t = RegOrLit::try_from(t)?;
+            let o = RegOrLit::try_from(o)?;
+            Box::new(StorbO { s, t, o })
+        }
         Memory::Push { d } => {
-            let d = RegisterSpecifier::from(d);
+            let d = RegisterSpecifier::try_from(d)?;
             Box::new(Push { d })
         }
         Memory::Pop { d } => {
-            let d = RegisterSpecifier::from(d);
+            let d = RegisterSpecifier::try_from(d)?;
             Box::new(Pop { d })
         }
-        _ => unimplemented!("{:?} is not implemented", instr),
-    }
+    };
+    Ok(ret)
 }
@@ -2,7 +2,11 @@ use edu_asm_parser::instruction::Misc;
 
 use crate::{
     behaviour::{Readable, Writeable},
+    config::DumpFormat,
+    error::RuntimeErrorKind,
     register::RegisterSpecifier,
+    snapshot::Snapshot,
+    StopReason,
 };
 
 use super::{Executable, RegOrLit};
@@ -10,11 +14,9 @@ use super::{Executable, RegOrLit};
 pub(crate) struct Halt {}
 
 impl Executable for Halt {
-    fn execute(&self, _: &mut crate::State) {
-        println!("\n EXECUTION HALTED INDEFINITLY");
-        loop {
-            std::thread::sleep(std::time::Duration::from_secs(60));
-        }
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        state.stop = Some(StopReason::Halted);
+        Ok(())
     }
 }
 
@@ -23,9 +25,10 @@ pub(crate) struct Exit<S: Readable> {
 }
 
 impl<S: Readable> Executable for Exit<S> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let exit_value = self.s.get_signed(state);
-        std::process::exit(exit_value as i32);
+        state.stop = Some(StopReason::Exited(exit_value));
+        Ok(())
     }
 }
 
@@ -34,11 +37,13 @@ pub(crate) struct Print<S: Readable> {
 }
 
 impl<S: Readable> Executable for Print<S> {
-    fn execute(&self, state: &mut crate::State) {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
         let value = self.s.get_unsigned(state);
         let value_u8 = value as u8;
-        let value_char = value_u8 as char;
-        print!("{}", value_char);
+        state
+            .io
+            .write(&[value_u8])
+            .map_err(|e| RuntimeErrorKind::Io(e.to_string()))
     }
 }
 
@@ -47,39 +52,59 @@ pub(crate) struct Read<S: Writeable> {
 }
 
 impl<S: Writeable> Executable for Read<S> {
-    fn execute(&self, state: &mut crate::State) {
-        use std::io::Read;
-
-        let mut inp = std::io::stdin();
-        let mut buf = [0u8; 1];
-        inp.read_exact(&mut buf).unwrap();
-        let buf_val = buf[0] as u64;
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let buf = state
+            .io
+            .read_byte()
+            .map_err(|e| RuntimeErrorKind::Io(e.to_string()))?;
+        let buf_val = buf as u64;
         self.s.set_unsigned(state, buf_val);
+        Ok(())
+    }
+}
+
+pub(crate) struct Dump {}
+
+impl Executable for Dump {
+    fn execute(&self, state: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        let config = state.config.dump;
+        let snapshot = Snapshot::take(state, config.memory);
+        let dump = match config.format {
+            DumpFormat::Text => snapshot.to_text(),
+            DumpFormat::Json => snapshot.to_json() + "\n",
+        };
+        state
+            .io
+            .write(dump.as_bytes())
+            .map_err(|e| RuntimeErrorKind::Io(e.to_string()))
     }
 }
 
 pub(crate) struct Nop {}
 
 impl Executable for Nop {
-    fn execute(&self, _: &mut crate::State) {}
+    fn execute(&self, _: &mut crate::State) -> Result<(), RuntimeErrorKind> {
+        Ok(())
+    }
 }
 
-pub(super) fn transpile_misc(instr: Misc) -> Box<dyn Executable> {
-    match instr {
+pub(super) fn transpile_misc(instr: Misc) -> Result<Box<dyn Executable>, RuntimeErrorKind> {
+    let ret: Box<dyn Executable> = match instr {
         Misc::Halt => Box::new(Halt {}),
         Misc::Exit { s } => {
-            let s = RegOrLit::from(s);
+            let s = RegOrLit::try_from(s)?;
             Box::new(Exit { s })
         }
         Misc::Print { s } => {
-            let s = RegOrLit::from(s);
+            let s = RegOrLit::try_from(s)?;
             Box::new(Print { s })
         }
         Misc::Read { s } => {
-            let s = RegisterSpecifier::from(s);
+            let s = RegisterSpecifier::try_from(s)?;
             Box::new(Read { s })
         }
+        Misc::Dump => Box::new(Dump {}),
         Misc::Nop => Box::new(Nop {}),
-        _ => unimplemented!("{:?} is unimplemented", instr),
-    }
+    };
+    Ok(ret)
 }
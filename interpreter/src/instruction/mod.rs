@@ -1,15 +1,34 @@
 use edu_asm_parser::instruction::{Instruction, RegisterOrLiteral};
 
-use crate::{behaviour::Readable, literal::Literal, register::RegisterSpecifier, State};
+use crate::{
+    behaviour::Readable, config::OverflowMode, error::RuntimeErrorKind, literal::Literal,
+    register::RegisterSpecifier, State,
+};
 
 pub(crate) mod arithmetic_base;
+pub(crate) mod arithmetic_bit_logic;
 pub(crate) mod arithmetic_mult_div;
+pub(crate) mod arithmetic_shift;
 pub(crate) mod control_flow;
 pub(crate) mod memory;
 pub(crate) mod misc;
 
 pub(crate) trait Executable {
-    fn execute(&self, state: &mut State);
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeErrorKind>;
+}
+
+/// Applies the overflow mode of the vm to the result of an `overflowing_*` operation.
+///
+/// The wrapped result is used, unless the vm runs in checked mode and the operation overflowed.
+#[inline]
+pub(crate) fn apply_overflow<V>(
+    state: &State,
+    (value, overflowed): (V, bool),
+) -> Result<V, RuntimeErrorKind> {
+    if overflowed && state.config.overflow == OverflowMode::Checked {
+        return Err(RuntimeErrorKind::Overflow);
+    }
+    Ok(value)
 }
 
 pub(crate) enum RegOrLit {
@@ -17,12 +36,14 @@ pub(crate) enum RegOrLit {
     Literal(Literal),
 }
 
-impl From<RegisterOrLiteral> for RegOrLit {
+impl TryFrom<RegisterOrLiteral> for RegOrLit {
+    type Error = RuntimeErrorKind;
+
     #[inline]
-    fn from(rl: RegisterOrLiteral) -> Self {
+    fn try_from(rl: RegisterOrLiteral) -> Result<Self, Self::Error> {
         match rl {
-            RegisterOrLiteral::Register(r) => RegOrLit::Register(r.into()),
-            RegisterOrLiteral::Literal(l) => RegOrLit::Literal(l.into()),
+            RegisterOrLiteral::Register(r) => Ok(RegOrLit::Register(r.try_into()?)),
+            RegisterOrLiteral::Literal(l) => Ok(RegOrLit::Literal(l.into())),
         }
     }
 }
@@ -46,15 +67,18 @@ impl Readable for RegOrLit {
 }
 
 #[inline]
-pub(crate) fn transpile_instr(instr: Instruction) -> Box<dyn Executable> {
+pub(crate) fn transpile_instr(instr: Instruction) -> Result<Box<dyn Executable>, RuntimeErrorKind> {
     match instr {
         Instruction::ArithmeticBase(i) => arithmetic_base::transpile_arithmetic_base(i),
+        Instruction::ArithmeticShift(i) => arithmetic_shift::transpile_arithmetic_shift(i),
+        Instruction::ArithmeticBitLogic(i) => {
+            arithmetic_bit_logic::transpile_arithmetic_bit_logic(i)
+        }
         Instruction::ArithmeticMultDivEasy(i) => {
             arithmetic_mult_div::transpile_arithmetic_mult_div(i)
         }
         Instruction::ControlFlow(i) => control_flow::transpile_control_flow(i),
         Instruction::Memory(i) => memory::transpile_memory(i),
         Instruction::Misc(i) => misc::transpile_misc(i),
-        _ => unimplemented!("instruction {:?} is unimplemented", instr),
     }
 }
@@ -0,0 +1,120 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufReader, Read, Write},
+    path::Path,
+    rc::Rc,
+};
+
+/// The input and output the instructions `print`, `read` and `dump` go through
+pub trait Io {
+    /// Reads a single byte, fails with [`io::ErrorKind::UnexpectedEof`] if the input is exhausted
+    fn read_byte(&mut self) -> io::Result<u8>;
+    /// Writes all of `bytes` to the output
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()>;
+}
+
+/// Reads from stdin and writes to stdout
+#[derive(Default)]
+pub struct StdIo {}
+
+impl Io for StdIo {
+    fn read_byte(&mut self) -> io::Result<u8> {
+        io::stdout().flush()?;
+        let mut buf = [0u8; 1];
+        io::stdin().read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        io::stdout().write_all(bytes)
+    }
+}
+
+/// Reads from an in-memory input and collects the output in memory.
+///
+/// Clones share the same buffers, so a clone can be handed to the [`crate::Vm`] while the
+/// original is kept to inspect the output.
+#[derive(Clone, Default)]
+pub struct BufferIo {
+    input: Rc<RefCell<VecDeque<u8>>>,
+    output: Rc<RefCell<Vec<u8>>>,
+}
+
+impl BufferIo {
+    pub fn new(input: impl Into<Vec<u8>>) -> BufferIo {
+        BufferIo {
+            input: Rc::new(RefCell::new(input.into().into())),
+            output: Rc::default(),
+        }
+    }
+
+    /// Returns everything written so far
+    pub fn output(&self) -> Vec<u8> {
+        self.output.borrow().clone()
+    }
+}
+
+impl Io for BufferIo {
+    fn read_byte(&mut self) -> io::Result<u8> {
+        self.input
+            .borrow_mut()
+            .pop_front()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.output.borrow_mut().extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+/// Reads from one file and writes to another
+pub struct FileIo {
+    input: BufReader<File>,
+    output: File,
+}
+
+impl FileIo {
+    /// Opens `input` for reading and creates or truncates `output` for writing
+    pub fn open(input: impl AsRef<Path>, output: impl AsRef<Path>) -> io::Result<FileIo> {
+        Ok(FileIo {
+            input: BufReader::new(File::open(input)?),
+            output: File::create(output)?,
+        })
+    }
+}
+
+impl Io for FileIo {
+    fn read_byte(&mut self) -> io::Result<u8> {
+        let mut buf = [0u8; 1];
+        self.input.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.output.write_all(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_io_shares_output() {
+        let io = BufferIo::new("ab");
+        let mut handle = io.clone();
+
+        assert_eq!(handle.read_byte().unwrap(), b'a');
+        assert_eq!(handle.read_byte().unwrap(), b'b');
+        assert_eq!(
+            handle.read_byte().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        handle.write(b"out").unwrap();
+
+        assert_eq!(io.output(), b"out");
+    }
+}
@@ -1,115 +1,543 @@
-use std::{collections::HashMap, rc::Rc};
-
-use edu_asm_parser::{instruction::Instruction, label::LocAwLabel, PureElement};
-use instruction::{transpile_instr, Executable};
-use register::RegisterCollection;
+use config::{Config, ErrorMode};
+use edu_asm_assembler::{
+    decode::{decode_instruction, MAX_INSTRUCTION_SIZE},
+    program::{DebugLine, Program},
+};
+use error::{RuntimeError, RuntimeErrorKind};
+use instruction::transpile_instr;
+use io::{Io, StdIo};
+use memory::{Memory, MemoryLayout, WORD_SIZE};
+use register::{InstructionRegister, RegisterBehaviour, RegisterCollection};
+use snapshot::Snapshot;
 
 pub(crate) mod behaviour;
+pub mod config;
+pub mod error;
 pub(crate) mod instruction;
+pub mod io;
 pub(crate) mod literal;
+pub(crate) mod memory;
 pub(crate) mod register;
+pub mod snapshot;
 
-pub(crate) struct Stack {
-    inner: Vec<u64>,
+pub(crate) struct State {
+    registers: RegisterCollection,
+    memory: Memory,
+    io: Box<dyn Io>,
+    config: Config,
+    stop: Option<StopReason>,
 }
 
-impl Stack {
-    pub(crate) fn push(&mut self, value: u64) {
-        self.inner.push(value)
+impl State {
+    fn new(program: &Program, io: Box<dyn Io>, config: Config) -> State {
+        let layout = MemoryLayout {
+            code_size: program.code.len() as u64,
+            data_size: program.data.len() as u64,
+            stack_size: config.stack_size,
+            ..MemoryLayout::default()
+        };
+        let mut memory = Memory::new(layout);
+        memory
+            .load_image(layout.code_start(), &program.code)
+            .expect("the code section is part of the memory");
+        memory
+            .load_image(layout.data_start(), &program.data)
+            .expect("the data section is part of the memory");
+
+        let mut registers = RegisterCollection::default();
+        registers.s.beg.set_unsigned(layout.stack_start());
+        registers.s.end.set_unsigned(layout.stack_start());
+        registers.m.ins = InstructionRegister::new(
+            usize::try_from(program.entry).expect("runtime archtiecture is to small"),
+        );
+
+        State {
+            registers,
+            memory,
+            io,
+            config,
+            stop: None,
+        }
     }
 
-    pub(crate) fn pop(&mut self) -> Option<u64> {
-        self.inner.pop()
+    /// Pushes `value` onto the stack at `$S_E` and moves `$S_E` to the next word
+    pub(crate) fn push(&mut self, value: u64) -> Result<(), RuntimeErrorKind> {
+        let end = self.registers.s.end.get_unsigned();
+        let limit = self.memory.layout().static_end();
+        if end.saturating_add(WORD_SIZE) > limit {
+            return Err(RuntimeErrorKind::StackOverflow);
+        }
+        self.memory.store_word(end, value)?;
+        self.registers.s.end.set_unsigned(end + WORD_SIZE);
+        Ok(())
     }
-}
 
-pub(crate) struct State {
-    registers: RegisterCollection,
-    stack: Stack,
+    /// Moves `$S_E` to the previous word and returns it, the stack is empty when `$S_E` reaches
+    /// `$S_B`
+    pub(crate) fn pop(&mut self) -> Result<u64, RuntimeErrorKind> {
+        let beg = self.registers.s.beg.get_unsigned();
+        let end = self.registers.s.end.get_unsigned();
+        if end < beg.saturating_add(WORD_SIZE) {
+            return Err(RuntimeErrorKind::StackUnderflow);
+        }
+        let value = self.memory.load_word(end - WORD_SIZE)?;
+        self.registers.s.end.set_unsigned(end - WORD_SIZE);
+        Ok(value)
+    }
 }
 
-impl State {
-    fn new() -> State {
-        let stack = Stack { inner: Vec::new() };
-        let registers = RegisterCollection::default();
+/// Fetches, decodes and executes the instruction `$I` points to
+fn step(state: &mut State) -> Result<(), Box<RuntimeError>> {
+    let address = state.registers.m.ins.current();
+    let fault = |kind, line, operand| {
+        Box::new(RuntimeError {
+            kind,
+            index: address,
+            line,
+            operand,
+            location: None,
+        })
+    };
+
+    let layout = *state.memory.layout();
+    let address_u64 = address as u64;
+    if address_u64 >= layout.data_start() {
+        return Err(fault(
+            RuntimeErrorKind::PcOutOfRange(address_u64),
+            None,
+            None,
+        ));
+    }
+
+    let mut buf = [0u8; MAX_INSTRUCTION_SIZE];
+    let code_left = (layout.data_start() - address_u64).min(MAX_INSTRUCTION_SIZE as u64);
+    let available = state
+        .memory
+        .read_bytes(address_u64, &mut buf[..code_left as usize]);
+    let (instruction, len) = decode_instruction(&buf[..available])
+        .map_err(|e| fault(RuntimeErrorKind::InvalidInstruction(e), None, None))?;
 
-        State { stack, registers }
+    let operand_fault = |kind: RuntimeErrorKind| {
+        let operand = kind.operand(&instruction);
+        fault(kind, Some(instruction.to_string()), operand)
+    };
+    let element = transpile_instr(instruction.clone()).map_err(operand_fault)?;
+    state.registers.m.ins.begin(len);
+    if let Err(e) = element.execute(state) {
+        match e.error_code() {
+            Some(code) if state.config.errors == ErrorMode::Flag => {
+                state.registers.m.err.set_unsigned(code)
+            }
+            _ => return Err(operand_fault(e)),
+        }
     }
+    state.registers.m.ins.finish();
+    Ok(())
+}
+
+/// The reason the execution of a program stopped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    /// The program executed `exit` with the contained value
+    Exited(i64),
+    /// The program executed `halt`
+    Halted,
+    /// The program faulted
+    Fault(RuntimeError),
+    /// The instruction budget passed to [`Vm::run_for`] was used up
+    BudgetExhausted,
+}
+
+/// A virtual machine executing a single program.
+///
+/// Once the program stopped by exiting, halting or faulting, the machine keeps returning the same
+/// [`StopReason`] without executing anything.
+pub struct Vm {
+    state: State,
+    /// Source locations of the instructions, used to locate faults
+    debug_lines: Vec<DebugLine>,
 }
 
-#[inline]
-pub fn reduce_label_map(map: HashMap<String, Rc<LocAwLabel>>) -> HashMap<String, Rc<LocAwLabel>> {
-    let mut ret = HashMap::with_capacity(map.capacity());
+impl Vm {
+    /// Creates a machine, that executes `program`, as produced by `edu_asm_assembler::assemble`.
+    ///
+    /// The data section of the program is loaded after its code, execution starts at its entry
+    /// point. `print`, `read` and `dump` use stdin and stdout.
+    pub fn new(program: &Program) -> Vm {
+        Vm::with_io(program, Box::new(StdIo::default()))
+    }
 
-    let mut labels: Vec<LocAwLabel> = map.into_values().map(|e| (*e).clone()).collect();
+    /// Creates a machine like [`Vm::new`], that performs its input and output through `io`
+    pub fn with_io(program: &Program, io: Box<dyn Io>) -> Vm {
+        Vm::with_config(program, io, Config::default())
+    }
 
-    labels.sort_by_key(|e| e.loc);
+    /// Creates a machine like [`Vm::with_io`], that behaves as described by `config`
+    pub fn with_config(program: &Program, io: Box<dyn Io>, config: Config) -> Vm {
+        Vm {
+            state: State::new(program, io, config),
+            debug_lines: program.debug_lines.clone(),
+        }
+    }
 
-    labels
-        .iter()
-        .enumerate()
-        .map(|e| {
-            let index = e.0;
-            let decremented = e.1.loc - index;
-            LocAwLabel {
-                loc: decremented,
-                name: e.1.name.clone(),
+    pub fn config(&self) -> &Config {
+        &self.state.config
+    }
+
+    /// Captures the registers, the stack and, if `memory` is set, the touched memory pages
+    pub fn snapshot(&self, memory: bool) -> Snapshot {
+        Snapshot::take(&self.state, memory)
+    }
+
+    /// Executes a single instruction, returns the reason if the program stopped
+    pub fn step(&mut self) -> Option<StopReason> {
+        if self.state.stop.is_none() {
+            if let Err(mut e) = step(&mut self.state) {
+                e.location = self
+                    .debug_lines
+                    .binary_search_by_key(&(e.index as u64), |l| l.offset)
+                    .ok()
+                    .map(|index| self.debug_lines[index].clone());
+                self.state.stop = Some(StopReason::Fault(*e));
             }
-        })
-        .map(Rc::new)
-        .for_each(|e| {
-            ret.insert(e.name.clone(), e);
-        });
+        }
+        self.state.stop.clone()
+    }
 
-    ret
-}
+    /// Executes instructions until the program stops
+    pub fn run(&mut self) -> StopReason {
+        loop {
+            if let Some(reason) = self.step() {
+                return reason;
+            }
+        }
+    }
 
-#[inline]
-pub fn update_pure_elements(
-    map: HashMap<String, Rc<LocAwLabel>>,
-    elements: Vec<PureElement>,
-) -> Vec<PureElement> {
-    elements
-        .iter()
-        .filter(|e| matches!(e, PureElement::Label(_)))
-        .map(|e| match e {
-            PureElement::Instruction(Instruction::ControlFlow(c)) => {
-                let mut locale = c.clone();
-                match locale.get_label() {
-                    Some(d) => {
-                        let loc_label = map.get(&d.content).cloned().unwrap();
-                        locale.hydrate(loc_label);
-                        PureElement::Instruction(Instruction::ControlFlow(locale))
-                    }
-                    None => PureElement::Instruction(Instruction::ControlFlow(locale)),
-                }
+    /// Executes at most `n_instructions` instructions.
+    ///
+    /// Returns [`StopReason::BudgetExhausted`] if the program is still running afterwards.
+    pub fn run_for(&mut self, n_instructions: u64) -> StopReason {
+        for _ in 0..n_instructions {
+            if let Some(reason) = self.step() {
+                return reason;
             }
-            _ => e.clone(),
-        })
-        .collect()
+        }
+        self.state
+            .stop
+            .clone()
+            .unwrap_or(StopReason::BudgetExhausted)
+    }
 }
 
-pub fn execute(elements: Vec<PureElement>, map: HashMap<String, Rc<LocAwLabel>>) {
-    let map = reduce_label_map(map);
-    let elements = update_pure_elements(map, elements);
-    let elements: Vec<Box<dyn Executable>> = elements
-        .iter()
-        .map(|e| match e {
-            PureElement::Instruction(d) => d,
-            _ => panic!("FUUUUUUCK"),
-        })
-        .map(|e| transpile_instr(e.clone()))
-        .collect();
-    let mut state = State::new();
-    loop {
-        let index = state.registers.m.ins.inc();
-        let element = elements.get(index).unwrap();
-        element.execute(&mut state);
-    }
+/// Executes `program` until it stops, see [`Vm`]
+pub fn execute(program: &Program) -> StopReason {
+    Vm::new(program).run()
 }
 
 #[cfg(test)]
 mod tests {
+    use edu_asm_assembler::{assemble, assemble_program, DEFAULT_ENTRY};
+    use edu_asm_parser::parse;
+
+    use super::*;
+    use crate::{
+        config::{DumpConfig, DumpFormat, OverflowMode},
+        io::BufferIo,
+    };
+
+    fn vm(source: &str) -> Vm {
+        vm_with_config(source, Config::default())
+    }
+
+    fn vm_with_config(source: &str, config: Config) -> Vm {
+        vm_with_io(source, &BufferIo::default(), config)
+    }
+
+    fn vm_with_io(source: &str, io: &BufferIo, config: Config) -> Vm {
+        let (parsed, _) = parse(source).unwrap();
+        let program = assemble_program(parsed, DEFAULT_ENTRY).unwrap();
+        Vm::with_config(&program, Box::new(io.clone()), config)
+    }
+
+    fn run_source(source: &str) -> RuntimeError {
+        match vm(source).run() {
+            StopReason::Fault(e) => e,
+            reason => panic!("expected a fault, got {:?}", reason),
+        }
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let error = run_source("_start:\n\tdivtu_e $G_0 $G_1 10u $Z\n");
+
+        assert_eq!(error.kind, RuntimeErrorKind::DivisionByZero);
+        assert_eq!(error.index, 0);
+        assert_eq!(error.line.as_deref(), Some("divtu_e $G_0 $G_1 10u $Z"));
+        assert_eq!(error.operand, Some(3));
+        assert_eq!(
+            error.location.map(|l| (l.line, l.column, l.operands)),
+            Some((1, 1, vec![9, 14, 19, 23]))
+        );
+    }
+
+    #[test]
+    fn stack_underflow() {
+        let error = run_source("_start:\n\tnop\n\tpop $G_0\n");
+
+        assert_eq!(error.kind, RuntimeErrorKind::StackUnderflow);
+        assert_eq!(error.index, 3);
+        assert_eq!(error.operand, None);
+    }
+
+    #[test]
+    fn invalid_memory_access() {
+        let error = run_source("_start:\n\tstorbo $Z 0xFFFFFFFFFFu 1\n");
+
+        assert_eq!(
+            error.kind,
+            RuntimeErrorKind::InvalidMemoryAccess(0x10000000000)
+        );
+        assert_eq!(error.operand, Some(1));
+        assert_eq!(error.location.map(|l| l.operands), Some(vec![8, 11, 25]));
+    }
+
+    #[test]
+    fn running_past_the_code() {
+        let error = run_source("_start:\n\tnop\n");
+
+        assert_eq!(error.kind, RuntimeErrorKind::PcOutOfRange(3));
+        assert_eq!(error.line, None);
+    }
+
+    #[test]
+    fn exit_code() {
+        let mut vm = vm("_start:\n\tmov $G_0 7\n\texit $G_0\n");
+
+        assert_eq!(vm.step(), None);
+        assert_eq!(vm.step(), Some(StopReason::Exited(7)));
+        assert_eq!(vm.run(), StopReason::Exited(7));
+    }
+
+    #[test]
+    fn halt() {
+        assert_eq!(vm("_start:\n\thalt\n").run(), StopReason::Halted);
+    }
+
+    #[test]
+    fn execute_from_entry() {
+        let source =
+            "helper:\n\thalt\n_start:\n\tmov $G_0 :value\n\tloadb $G_1 $G_0\n\texit $G_1\n\
+            .data\nvalue:\n\t.byte 42\n";
+        let (parsed, _) = parse(source).unwrap();
+
+        assert_eq!(execute(&assemble(parsed).unwrap()), StopReason::Exited(42));
+    }
+
+    #[test]
+    fn budget() {
+        let mut vm = vm("_start:\n\tnop\n\tjmp :_start\n");
+
+        assert_eq!(vm.run_for(1000), StopReason::BudgetExhausted);
+        assert_eq!(vm.run_for(0), StopReason::BudgetExhausted);
+    }
+
+    #[test]
+    fn echo() {
+        let io = BufferIo::new("hi");
+        let program =
+            "_start:\n\tread $G_0\n\tprint $G_0\n\tread $G_0\n\tprint $G_0\n\tread $G_0\n";
+
+        let reason = vm_with_io(program, &io, Config::default()).run();
+
+        assert_eq!(io.output(), b"hi");
+        match reason {
+            StopReason::Fault(e) => assert!(matches!(e.kind, RuntimeErrorKind::Io(_))),
+            reason => panic!("expected a fault, got {:?}", reason),
+        }
+    }
+
+    #[test]
+    fn masks_and_shifts() {
+        let program = "_start:
+            mov $G_0 240
+            lshli $G_0 4
+            ori $G_0 10
+            andt $G_1 $G_0 255
+            xori $G_1 255
+            nott $G_2 $G_1
+            noi $G_2
+            ashrt $G_3 -64 70
+            addts $G_1 $G_1 $G_3
+            exit $G_1
+        ";
+
+        assert_eq!(vm(program).run(), StopReason::Exited(0xf4));
+    }
+
+    #[test]
+    fn dump_json() {
+        let io = BufferIo::new("");
+        let config = Config {
+            dump: DumpConfig {
+                format: DumpFormat::Json,
+                memory: false,
+            },
+            ..Config::default()
+        };
+        let mut vm = vm_with_io("_start:\n\tpush $Z\n\tdump\n\texit 0\n", &io, config);
+
+        assert_eq!(vm.run(), StopReason::Exited(0));
+        let output = String::from_utf8(io.output()).unwrap();
+        assert!(output.starts_with(r#"{"registers":[{"name":"$G_0","signed":0"#));
+        assert!(output.ends_with("\"stack\":[0],\"pages\":[]}\n"));
+        assert_eq!(vm.snapshot(false).stack, [0]);
+    }
+
+    #[test]
+    fn error_flag() {
+        let program = "_start:
+            divtu_e $G_0 $G_1 10u $Z
+            mov $G_2 $E
+            pop $G_0
+            addtu $G_2 $G_2 $E
+            mov $E $Z
+            exit $G_2
+        ";
+        let mut vm = vm_with_config(
+            program,
+            Config {
+                errors: ErrorMode::Flag,
+                ..Config::default()
+            },
+        );
+
+        assert_eq!(vm.run(), StopReason::Exited(5));
+        assert_eq!(vm.snapshot(false).registers.last().unwrap().value, 0);
+    }
+
+    #[test]
+    fn ret_underflow_keeps_return_register() {
+        let program =
+            "_start:\n\tmov $R 7\n\tmov $G_1 9\n\tret $G_1\n\taddtu $G_0 $R $E\n\texit $G_0\n";
+        let config = Config {
+            errors: ErrorMode::Flag,
+            ..Config::default()
+        };
+
+        assert_eq!(
+            vm_with_config(program, config).run(),
+            StopReason::Exited(10)
+        );
+    }
+
+    #[test]
+    fn overflow_boundaries() {
+        const MIN: &str = "9223372036854775808u";
+        const MAX: &str = "9223372036854775807";
+        const U_MAX: &str = "18446744073709551615u";
+        let cases = [
+            (format!("addts $G_0 {} 1", MAX), i64::MIN),
+            (format!("addtu $G_0 {} 1u", U_MAX), 0),
+            (format!("subts $G_0 {} 1", MIN), i64::MAX),
+            ("subtu $G_0 0u 1u".to_string(), -1),
+            (format!("mults_e $G_0 {} 2", MAX), -2),
+            (format!("multu_e $G_0 {} {}", U_MAX, U_MAX), 1),
+            (format!("divts_e $G_0 $G_1 {} -1", MIN), i64::MIN),
+        ];
+
+        for (instruction, wrapped) in cases {
+            let program = format!("_start:\n\t{}\n\texit $G_0\n", instruction);
+            let run = |overflow, errors| {
+                let config = Config {
+                    overflow,
+                    errors,
+                    ..Config::default()
+                };
+                vm_with_config(&program, config).run()
+            };
+
+            assert_eq!(
+                run(OverflowMode::Wrapping, ErrorMode::Trap),
+                StopReason::Exited(wrapped),
+                "{}",
+                instruction
+            );
+            match run(OverflowMode::Checked, ErrorMode::Trap) {
+                StopReason::Fault(e) => assert_eq!(e.kind, RuntimeErrorKind::Overflow),
+                reason => panic!("{}: expected a fault, got {:?}", instruction, reason),
+            }
+            let config = Config {
+                overflow: OverflowMode::Checked,
+                errors: ErrorMode::Flag,
+                ..Config::default()
+            };
+            let mut flagged =
+                vm_with_config(&format!("_start:\n\t{}\n\texit $E\n", instruction), config);
+            assert_eq!(flagged.run(), StopReason::Exited(1), "{}", instruction);
+        }
+    }
+
+    #[test]
+    fn stack_overflow() {
+        let program = "_start:\n\tcal :_start\n";
+        let config = Config {
+            stack_size: 64,
+            ..Config::default()
+        };
+        let mut vm = vm_with_config(program, config);
+
+        match vm.run() {
+            StopReason::Fault(e) => assert_eq!(e.kind, RuntimeErrorKind::StackOverflow),
+            reason => panic!("expected a fault, got {:?}", reason),
+        }
+        assert_eq!(vm.snapshot(false).stack.len(), 8);
+    }
+
+    #[test]
+    fn stack_registers() {
+        let mut vm = vm("_start:\n\tmov $G_0 1\n\tpush $G_0\n\tmov $G_0 2\n\tpush $G_0\n\tmov $G_0 $S_B\n\tmov $G_1 $S_E\n\tsubtu $G_0 $G_1 $G_0\n\texit $G_0\n");
+
+        assert_eq!(vm.run(), StopReason::Exited(16));
+        assert_eq!(vm.snapshot(false).stack, [1, 2]);
+    }
+
+    #[test]
+    fn data_section() {
+        let io = BufferIo::new("");
+        let program = "_start:
+            mov $G_0 :text
+        loop:
+            loadb $G_1 $G_0
+            jmpeq $G_1 0 :end
+            print $G_1
+            addiu $G_0 1
+            jmp :loop
+        end:
+            mov $G_0 :number
+            load $G_0 $G_0
+            exit $G_0
+        .data
+        text:
+            .asciz \"hi\\n\"
+            .align 8
+        number:
+            .word 42
+        ";
+
+        assert_eq!(
+            vm_with_io(program, &io, Config::default()).run(),
+            StopReason::Exited(42)
+        );
+        assert_eq!(io.output(), b"hi\n");
+    }
+
+    #[test]
+    fn entry_point() {
+        let program = "helper:\n\tmov $G_0 3\n\tret $G_0\n_start:\n\tcal :helper\n\texit $R\n";
+
+        assert_eq!(vm(program).run(), StopReason::Exited(3));
+    }
+
     #[test]
     fn it_works() {
         let result = 2 + 2;
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use crate::error::RuntimeErrorKind;
+
+/// Size of a single memory page in bytes
+pub const PAGE_SIZE: u64 = 65_536;
+/// Size of a word in bytes
+pub const WORD_SIZE: u64 = 8;
+/// Default size of the stack segment in bytes
+pub const DEFAULT_STACK_SIZE: u64 = PAGE_SIZE;
+/// Default number of pages available in the dynamic memory
+pub const DEFAULT_DYNAMIC_PAGES: u64 = 16;
+
+type Page = Box<[u8; PAGE_SIZE as usize]>;
+
+/// Describes how the address space of the vm is divided.
+///
+/// The static memory starts at `0x00` with the code section, followed by the data section and the
+/// stack. The dynamic memory starts at the first page boundary after the static memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MemoryLayout {
+    pub(crate) code_size: u64,
+    pub(crate) data_size: u64,
+    pub(crate) stack_size: u64,
+    pub(crate) dynamic_pages: u64,
+}
+
+impl Default for MemoryLayout {
+    fn default() -> Self {
+        Self {
+            code_size: 0,
+            data_size: 0,
+            stack_size: DEFAULT_STACK_SIZE,
+            dynamic_pages: DEFAULT_DYNAMIC_PAGES,
+        }
+    }
+}
+
+impl MemoryLayout {
+    #[inline]
+    pub(crate) fn code_start(&self) -> u64 {
+        0
+    }
+
+    #[inline]
+    pub(crate) fn data_start(&self) -> u64 {
+        self.code_start() + self.code_size
+    }
+
+    #[inline]
+    pub(crate) fn stack_start(&self) -> u64 {
+        self.data_start() + self.data_size
+    }
+
+    #[inline]
+    pub(crate) fn static_end(&self) -> u64 {
+        self.stack_start() + self.stack_size
+    }
+
+    #[inline]
+    pub(crate) fn dynamic_start(&self) -> u64 {
+        self.static_end().div_ceil(PAGE_SIZE) * PAGE_SIZE
+    }
+
+    #[inline]
+    pub(crate) fn dynamic_end(&self) -> u64 {
+        self.dynamic_start() + self.dynamic_pages * PAGE_SIZE
+    }
+}
+
+/// The byte addressable, paged memory of the vm.
+///
+/// Pages are only backed by host memory once they are touched, reading from an untouched page
+/// yields zeros.
+pub(crate) struct Memory {
+    layout: MemoryLayout,
+    pages: HashMap<u64, Page>,
+}
+
+impl Memory {
+    pub(crate) fn new(layout: MemoryLayout) -> Self {
+        Self {
+            layout,
+            pages: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn layout(&self) -> &MemoryLayout {
+        &self.layout
+    }
+
+    /// Returns the address and contents of every page, that has been touched, ordered by address
+    pub(crate) fn touched_pages(&self) -> Vec<(u64, &[u8])> {
+        let mut pages: Vec<(u64, &[u8])> = self
+            .pages
+            .iter()
+            .map(|(index, page)| (index * PAGE_SIZE, &page[..]))
+            .collect();
+        pages.sort_by_key(|(address, _)| *address);
+        pages
+    }
+
+    /// Checks, that the `len` bytes starting at `address` are part of the memory
+    #[inline]
+    fn check_range(&self, address: u64, len: u64) -> Result<(), RuntimeErrorKind> {
+        if address.saturating_add(len - 1) >= self.layout.dynamic_end() {
+            return Err(RuntimeErrorKind::InvalidMemoryAccess(address));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn load_byte(&self, address: u64) -> Result<u8, RuntimeErrorKind> {
+        self.check_range(address, 1)?;
+        let page_index = address / PAGE_SIZE;
+        let page_offset = (address % PAGE_SIZE) as usize;
+        match self.pages.get(&page_index) {
+            Some(page) => Ok(page[page_offset]),
+            None => Ok(0),
+        }
+    }
+
+    pub(crate) fn store_byte(&mut self, address: u64, value: u8) -> Result<(), RuntimeErrorKind> {
+        self.check_range(address, 1)?;
+        let page_index = address / PAGE_SIZE;
+        let page_offset = (address % PAGE_SIZE) as usize;
+        let page = self
+            .pages
+            .entry(page_index)
+            .or_insert_with(|| Box::new([0u8; PAGE_SIZE as usize]));
+        page[page_offset] = value;
+        Ok(())
+    }
+
+    /// Copies `image` into the memory, starting at `address`
+    pub(crate) fn load_image(
+        &mut self,
+        address: u64,
+        image: &[u8],
+    ) -> Result<(), RuntimeErrorKind> {
+        for (i, byte) in image.iter().enumerate() {
+            self.store_byte(address + i as u64, *byte)?;
+        }
+        Ok(())
+    }
+
+    /// Fills `buf` with the memory starting at `address`, stopping at the end of the address space.
+    ///
+    /// Returns the number of bytes read.
+    pub(crate) fn read_bytes(&self, address: u64, buf: &mut [u8]) -> usize {
+        let available = self.layout.dynamic_end().saturating_sub(address);
+        let len = buf
+            .len()
+            .min(usize::try_from(available).unwrap_or(usize::MAX));
+        for (i, byte) in buf[..len].iter_mut().enumerate() {
+            *byte = self.load_byte(address + i as u64).unwrap_or_default();
+        }
+        len
+    }
+
+    pub(crate) fn load_word(&self, address: u64) -> Result<u64, RuntimeErrorKind> {
+        self.check_range(address, WORD_SIZE)?;
+        let mut bytes = [0u8; WORD_SIZE as usize];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = self.load_byte(address + i as u64)?;
+        }
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub(crate) fn store_word(&mut self, address: u64, value: u64) -> Result<(), RuntimeErrorKind> {
+        self.check_range(address, WORD_SIZE)?;
+        let bytes = value.to_le_bytes();
+        for (i, byte) in bytes.iter().enumerate() {
+            self.store_byte(address + i as u64, *byte)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_is_contiguous() {
+        let layout = MemoryLayout {
+            code_size: 100,
+            data_size: 20,
+            stack_size: 8,
+            dynamic_pages: 2,
+        };
+
+        assert_eq!(layout.data_start(), 100);
+        assert_eq!(layout.stack_start(), 120);
+        assert_eq!(layout.static_end(), 128);
+        assert_eq!(layout.dynamic_start(), PAGE_SIZE);
+        assert_eq!(layout.dynamic_end(), 3 * PAGE_SIZE);
+    }
+
+    #[test]
+    fn word_across_page_boundary() {
+        let mut memory = Memory::new(MemoryLayout::default());
+        let address = PAGE_SIZE - 3;
+
+        memory.store_word(address, 0x0102_0304_0506_0708).unwrap();
+
+        assert_eq!(memory.load_word(address), Ok(0x0102_0304_0506_0708));
+        assert_eq!(memory.load_byte(address), Ok(0x08));
+        assert_eq!(memory.load_byte(PAGE_SIZE), Ok(0x05));
+    }
+
+    #[test]
+    fn untouched_memory_is_zero() {
+        let memory = Memory::new(MemoryLayout::default());
+
+        assert_eq!(memory.load_word(0x1234), Ok(0));
+    }
+
+    #[test]
+    fn read_bytes_at_end_of_memory() {
+        let mut memory = Memory::new(MemoryLayout::default());
+        let end = memory.layout.dynamic_end();
+        memory.load_image(end - 2, &[1, 2]).unwrap();
+
+        let mut buf = [0u8; 4];
+        assert_eq!(memory.read_bytes(end - 2, &mut buf), 2);
+        assert_eq!(buf, [1, 2, 0, 0]);
+    }
+
+    #[test]
+    fn access_out_of_bounds() {
+        let mut memory = Memory::new(MemoryLayout::default());
+        let end = memory.layout.dynamic_end();
+
+        assert_eq!(
+            memory.load_byte(end),
+            Err(RuntimeErrorKind::InvalidMemoryAccess(end))
+        );
+        assert_eq!(
+            memory.store_word(end - 4, 0),
+            Err(RuntimeErrorKind::InvalidMemoryAccess(end - 4))
+        );
+        assert_eq!(memory.load_byte(end - 4), Ok(0));
+    }
+}
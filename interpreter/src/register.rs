@@ -2,7 +2,28 @@ use std::fmt::Debug;
 
 use edu_asm_parser::register::RegisterToken;
 
-use crate::behaviour::{Readable, Writeable};
+use crate::{
+    behaviour::{Readable, Writeable},
+    error::RuntimeErrorKind,
+};
+
+/// Every register an instruction can access, in the order they are dumped
+pub(crate) const REGISTERS: [RegisterToken; 14] = [
+    RegisterToken::GeneralPurpose(0),
+    RegisterToken::GeneralPurpose(1),
+    RegisterToken::GeneralPurpose(2),
+    RegisterToken::GeneralPurpose(3),
+    RegisterToken::GeneralPurpose(4),
+    RegisterToken::GeneralPurpose(5),
+    RegisterToken::GeneralPurpose(6),
+    RegisterToken::GeneralPurpose(7),
+    RegisterToken::StackBase,
+    RegisterToken::StackEnd,
+    RegisterToken::Return,
+    RegisterToken::Instruction,
+    RegisterToken::Zero,
+    RegisterToken::Error,
+];
 
 pub(crate) trait RegisterBehaviour {
     fn get_signed(&self) -> i64;
@@ -32,8 +53,8 @@ pub(crate) struct GeneralPurposeRegisters {
 
 #[derive(Default)]
 pub(crate) struct StackRegisters {
-    beg: ZeroRegister,
-    end: ZeroRegister,
+    pub(crate) beg: Register,
+    pub(crate) end: Register,
 }
 
 #[derive(Default)]
@@ -41,6 +62,7 @@ pub(crate) struct MiscRegisters {
     pub(crate) ret: Register,
     pub(crate) ins: InstructionRegister,
     pub(crate) zer: ZeroRegister,
+    pub(crate) err: Register,
 }
 
 #[derive(Default)]
@@ -109,20 +131,45 @@ impl RegisterBehaviour for Register {
     }
 }
 
+/// Holds the address of the instruction currently beeing executed.
+///
+/// The address of the following instruction is kept separately, so jumps only take effect after
+/// the current instruction finished.
 #[derive(Default)]
 pub(crate) struct InstructionRegister {
     counter: usize,
+    next: usize,
 }
 
 impl InstructionRegister {
-    pub(crate) fn inc(&mut self) -> usize {
-        let ret = self.counter;
-        self.counter += 1;
-        ret
+    /// Creates the register pointing at the instruction at `loc`
+    pub(crate) fn new(loc: usize) -> Self {
+        Self {
+            counter: loc,
+            next: loc,
+        }
+    }
+
+    /// Marks the start of the execution of the current instruction with a size of `len` bytes
+    pub(crate) fn begin(&mut self, len: usize) {
+        self.next = self.counter + len;
+    }
+
+    /// Moves the counter to the next instruction
+    pub(crate) fn finish(&mut self) {
+        self.counter = self.next;
+    }
+
+    pub(crate) fn current(&self) -> usize {
+        self.counter
+    }
+
+    pub(crate) fn next(&self) -> usize {
+        self.next
     }
 
     pub(crate) fn jump(&mut self, loc: usize) {
-        self.counter = loc;
+        self.next = loc;
     }
 }
 
@@ -140,14 +187,14 @@ impl RegisterBehaviour for InstructionRegister {
 
     #[cold]
     fn set_signed(&mut self, val: i64) {
-        let tmp: u64 = unsafe { std::mem::transmute(val) };
-        self.counter = usize::try_from(tmp)
+        let tmp = val as u64;
+        self.next = usize::try_from(tmp)
             .expect("value written into the program counter exceeded the archtiectures limits");
     }
 
     #[cold]
     fn set_unsigned(&mut self, val: u64) {
-        self.counter = usize::try_from(val)
+        self.next = usize::try_from(val)
             .expect("value written into the program counter exceeded the archtiectures limits")
     }
 }
@@ -166,6 +213,7 @@ pub(crate) enum RegisterSpecifier {
     R,
     I,
     Z,
+    E,
 }
 
 impl Readable for RegisterSpecifier {
@@ -185,6 +233,7 @@ impl Readable for RegisterSpecifier {
             RegisterSpecifier::R => state.registers.m.ret.get_signed(),
             RegisterSpecifier::I => state.registers.m.ins.get_signed(),
             RegisterSpecifier::Z => state.registers.m.zer.get_signed(),
+            RegisterSpecifier::E => state.registers.m.err.get_signed(),
         }
     }
 
@@ -204,6 +253,7 @@ impl Readable for RegisterSpecifier {
             RegisterSpecifier::R => state.registers.m.ret.get_unsigned(),
             RegisterSpecifier::I => state.registers.m.ins.get_unsigned(),
             RegisterSpecifier::Z => state.registers.m.zer.get_unsigned(),
+            RegisterSpecifier::E => state.registers.m.err.get_unsigned(),
         }
     }
 }
@@ -225,6 +275,7 @@ impl Writeable for RegisterSpecifier {
             RegisterSpecifier::R => state.registers.m.ret.set_signed(val),
             RegisterSpecifier::I => state.registers.m.ins.set_signed(val),
             RegisterSpecifier::Z => state.registers.m.zer.set_signed(val),
+            RegisterSpecifier::E => state.registers.m.err.set_signed(val),
         }
     }
 
@@ -244,13 +295,16 @@ impl Writeable for RegisterSpecifier {
             RegisterSpecifier::R => state.registers.m.ret.set_unsigned(val),
             RegisterSpecifier::I => state.registers.m.ins.set_unsigned(val),
             RegisterSpecifier::Z => state.registers.m.zer.set_unsigned(val),
+            RegisterSpecifier::E => state.registers.m.err.set_unsigned(val),
         }
     }
 }
 
-impl From<RegisterToken> for RegisterSpecifier {
-    fn from(rt: RegisterToken) -> Self {
-        match rt {
+impl TryFrom<RegisterToken> for RegisterSpecifier {
+    type Error = RuntimeErrorKind;
+
+    fn try_from(rt: RegisterToken) -> Result<Self, Self::Error> {
+        let ret = match rt {
             RegisterToken::GeneralPurpose(0) => Self::G0,
             RegisterToken::GeneralPurpose(1) => Self::G1,
             RegisterToken::GeneralPurpose(2) => Self::G2,
@@ -259,13 +313,50 @@ impl From<RegisterToken> for RegisterSpecifier {
             RegisterToken::GeneralPurpose(5) => Self::G5,
             RegisterToken::GeneralPurpose(6) => Self::G6,
             RegisterToken::GeneralPurpose(7) => Self::G7,
-            RegisterToken::GeneralPurpose(_) => panic!("general purpose register index is invalid"),
-            RegisterToken::StackBase => Self::SE,
-            RegisterToken::StackEnd => Self::SB,
+            RegisterToken::GeneralPurpose(_) => return Err(RuntimeErrorKind::InvalidRegister(rt)),
+            RegisterToken::StackBase => Self::SB,
+            RegisterToken::StackEnd => Self::SE,
             RegisterToken::Return => Self::R,
             RegisterToken::Instruction => Self::I,
             RegisterToken::Zero => Self::Z,
-            _ => unimplemented!("register specifier is unimplemented"),
+            RegisterToken::Error => Self::E,
+        };
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edu_asm_assembler::register::*;
+
+    use super::*;
+
+    /// The register code of the register a specifier accesses
+    fn code(specifier: &RegisterSpecifier) -> u8 {
+        match specifier {
+            RegisterSpecifier::G0 => G_0_CODE,
+            RegisterSpecifier::G1 => G_1_CODE,
+            RegisterSpecifier::G2 => G_2_CODE,
+            RegisterSpecifier::G3 => G_3_CODE,
+            RegisterSpecifier::G4 => G_4_CODE,
+            RegisterSpecifier::G5 => G_5_CODE,
+            RegisterSpecifier::G6 => G_6_CODE,
+            RegisterSpecifier::G7 => G_7_CODE,
+            RegisterSpecifier::SB => S_B_CODE,
+            RegisterSpecifier::SE => S_E_CODE,
+            RegisterSpecifier::R => R_CODE,
+            RegisterSpecifier::I => I_CODE,
+            RegisterSpecifier::Z => Z_CODE,
+            RegisterSpecifier::E => E_CODE,
+        }
+    }
+
+    #[test]
+    fn mapping_matches_assembler() {
+        for token in REGISTERS {
+            let specifier = RegisterSpecifier::try_from(token).unwrap();
+
+            assert_eq!(code(&specifier), encode_register_token(&token), "{}", token);
         }
     }
 }
@@ -0,0 +1,201 @@
+use std::fmt::Write;
+
+use crate::{
+    behaviour::Readable,
+    memory::WORD_SIZE,
+    register::{RegisterBehaviour, RegisterSpecifier, REGISTERS},
+    State,
+};
+
+/// Number of bytes shown per line of a memory dump
+const BYTES_PER_LINE: usize = 16;
+
+/// The value of a single register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterValue {
+    /// The name of the register as written in source, e.g. `$G_0`
+    pub name: String,
+    pub value: u64,
+}
+
+/// The contents of a memory page, that has been touched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageSnapshot {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// The state of the machine at a single point of the execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub registers: Vec<RegisterValue>,
+    /// The values on the stack, from bottom to top
+    pub stack: Vec<u64>,
+    /// The touched memory pages, empty if the memory wasn't requested
+    pub pages: Vec<PageSnapshot>,
+}
+
+impl Snapshot {
+    pub(crate) fn take(state: &State, memory: bool) -> Snapshot {
+        let registers = REGISTERS
+            .iter()
+            .map(|token| {
+                let register = RegisterSpecifier::try_from(*token)
+                    .expect("every register in `REGISTERS` is accessible");
+                RegisterValue {
+                    name: token.to_string(),
+                    value: register.get_unsigned(state),
+                }
+            })
+            .collect();
+
+        let pages = if memory {
+            state
+                .memory
+                .touched_pages()
+                .into_iter()
+                .map(|(address, bytes)| PageSnapshot {
+                    address,
+                    bytes: bytes.to_vec(),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Snapshot {
+            registers,
+            stack: Self::stack(state),
+            pages,
+        }
+    }
+
+    /// Reads the words between `$S_B` and `$S_E`, stopping early if the registers have been
+    /// moved outside of the memory
+    fn stack(state: &State) -> Vec<u64> {
+        let beg = state.registers.s.beg.get_unsigned();
+        let end = state.registers.s.end.get_unsigned();
+        let end = end.min(state.memory.layout().static_end());
+        (beg..end)
+            .step_by(WORD_SIZE as usize)
+            .take_while(|address| address + WORD_SIZE <= end)
+            .map_while(|address| state.memory.load_word(address).ok())
+            .collect()
+    }
+
+    /// Renders the snapshot as human readable text.
+    ///
+    /// Registers are shown signed, unsigned and in binary. Memory is shown as a hex dump, lines
+    /// containing only zeros are left out.
+    pub fn to_text(&self) -> String {
+        let mut ret = String::from("registers:\n");
+        for register in &self.registers {
+            let _ = writeln!(
+                ret,
+                "    {:<4} signed: {:<20} unsigned: {:<20} binary: {:#b}",
+                register.name, register.value as i64, register.value, register.value
+            );
+        }
+
+        let _ = writeln!(ret, "stack ({} values, bottom to top):", self.stack.len());
+        for (index, value) in self.stack.iter().enumerate() {
+            let _ = writeln!(ret, "    {:>4}: {} ({:#x})", index, value, value);
+        }
+
+        if !self.pages.is_empty() {
+            ret.push_str("memory:\n");
+        }
+        for page in &self.pages {
+            for (line, bytes) in page.bytes.chunks(BYTES_PER_LINE).enumerate() {
+                if bytes.iter().all(|b| *b == 0) {
+                    continue;
+                }
+                let address = page.address + (line * BYTES_PER_LINE) as u64;
+                let _ = write!(ret, "    {:#010x}:", address);
+                for byte in bytes {
+                    let _ = write!(ret, " {:02x}", byte);
+                }
+                ret.push('\n');
+            }
+        }
+        ret
+    }
+
+    /// Renders the snapshot as a single line JSON object.
+    ///
+    /// Registers are objects with `name`, `signed`, `unsigned` and `binary`, pages are objects
+    /// with `address` and their `bytes` as a hex string.
+    pub fn to_json(&self) -> String {
+        let registers: Vec<String> = self
+            .registers
+            .iter()
+            .map(|r| {
+                format!(
+                    r#"{{"name":"{}","signed":{},"unsigned":{},"binary":"{:#b}"}}"#,
+                    r.name, r.value as i64, r.value, r.value
+                )
+            })
+            .collect();
+        let stack: Vec<String> = self.stack.iter().map(|v| v.to_string()).collect();
+        let pages: Vec<String> = self
+            .pages
+            .iter()
+            .map(|p| {
+                let bytes: String = p.bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                format!(r#"{{"address":{},"bytes":"{}"}}"#, p.address, bytes)
+            })
+            .collect();
+        format!(
+            r#"{{"registers":[{}],"stack":[{}],"pages":[{}]}}"#,
+            registers.join(","),
+            stack.join(","),
+            pages.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        let mut bytes = vec![0u8; 32];
+        bytes[17] = 0xab;
+        Snapshot {
+            registers: vec![RegisterValue {
+                name: "$G_0".to_string(),
+                value: u64::MAX,
+            }],
+            stack: vec![1, 2],
+            pages: vec![PageSnapshot {
+                address: 0x10000,
+                bytes,
+            }],
+        }
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(
+            snapshot().to_text(),
+            format!(
+                "registers:\n    $G_0 signed: -1                   unsigned: 18446744073709551615 binary: {:#b}\n\
+                 stack (2 values, bottom to top):\n       0: 1 (0x1)\n       1: 2 (0x2)\n\
+                 memory:\n    0x00010010: 00 ab 00 00 00 00 00 00 00 00 00 00 00 00 00 00\n",
+                u64::MAX
+            )
+        );
+    }
+
+    #[test]
+    fn json() {
+        let mut snapshot = snapshot();
+        snapshot.pages[0].bytes.truncate(2);
+        snapshot.registers[0].value = 5;
+
+        assert_eq!(
+            snapshot.to_json(),
+            r#"{"registers":[{"name":"$G_0","signed":5,"unsigned":5,"binary":"0b101"}],"stack":[1,2],"pages":[{"address":65536,"bytes":"0000"}]}"#
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Runs every program in `tests/conformance` and checks it against the expectations in its header.
+//!
+//! The header consists of comment lines at the start of the file:
+//!
+//! * `# exit: <value>` - the program exits with `value`
+//! * `# fault: <message>` - the program stops with a fault, displayed as `message`
+//! * `# halt` - the program halts
+//! * `# input: <text>` - `text` is available to `read`
+//! * `# output: <text>` - the program prints exactly `text`
+//! * `# link: <file>` - the program is linked with `file` from `tests/conformance/lib`
+
+use std::{fs, path::Path};
+
+use edu_asm_assembler::{assemble_object, link::link, DEFAULT_ENTRY};
+use edu_asm_interpreter::{io::BufferIo, StopReason, Vm};
+use edu_asm_parser::parse;
+
+/// Number of instructions after which a conformance program is considered stuck
+const BUDGET: u64 = 100_000;
+
+#[derive(Default)]
+struct Expectation {
+    exit: Option<i64>,
+    fault: Option<String>,
+    halt: bool,
+    input: String,
+    output: String,
+    link: Vec<String>,
+}
+
+fn read_expectation(source: &str) -> Expectation {
+    let mut ret = Expectation::default();
+    for line in source.lines().map_while(|l| l.strip_prefix('#')) {
+        let line = line.trim();
+        if let Some(exit) = line.strip_prefix("exit:") {
+            ret.exit = Some(exit.trim().parse().unwrap());
+        } else if let Some(fault) = line.strip_prefix("fault:") {
+            ret.fault = Some(fault.trim().to_string());
+        } else if let Some(input) = line.strip_prefix("input:") {
+            ret.input = input.trim().to_string();
+        } else if let Some(output) = line.strip_prefix("output:") {
+            ret.output = output.trim().to_string();
+        } else if let Some(link) = line.strip_prefix("link:") {
+            ret.link.push(link.trim().to_string());
+        } else if line == "halt" {
+            ret.halt = true;
+        }
+    }
+    ret
+}
+
+fn check(path: &Path) {
+    let name = path.display();
+    let source = fs::read_to_string(path).unwrap();
+    let expectation = read_expectation(&source);
+    let object = |source: &str| {
+        let (parsed, _) = parse(source).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assemble_object(parsed)
+    };
+    let lib = path.with_file_name("lib");
+    let mut objects = vec![object(&source)];
+    for file in &expectation.link {
+        objects.push(object(&fs::read_to_string(lib.join(file)).unwrap()));
+    }
+    let io = BufferIo::new(expectation.input.as_bytes());
+
+    let program = link(&objects, DEFAULT_ENTRY).unwrap_or_else(|e| panic!("{}: {}", name, e));
+    let reason = Vm::with_io(&program, Box::new(io.clone())).run_for(BUDGET);
+
+    match reason {
+        StopReason::Exited(value) => assert_eq!(Some(value), expectation.exit, "{}", name),
+        StopReason::Fault(e) => {
+            assert_eq!(Some(e.kind.to_string()), expectation.fault, "{}", name)
+        }
+        StopReason::Halted => assert!(expectation.halt, "{} halted", name),
+        StopReason::BudgetExhausted => panic!("{} didn't stop", name),
+    }
+    assert_eq!(
+        String::from_utf8(io.output()).unwrap(),
+        expectation.output,
+        "{}",
+        name
+    );
+}
+
+#[test]
+fn conformance() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance");
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "edu"))
+        .collect();
+    paths.sort();
+
+    assert!(!paths.is_empty());
+    for path in paths {
+        check(&path);
+    }
+}
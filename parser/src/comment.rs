@@ -1,16 +1,33 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-
-const COMMENT_EXP: &str = "#.*";
-
-lazy_static! {
-    static ref COMMENT_RE: Regex = Regex::new(COMMENT_EXP).unwrap();
-}
-
+/// Removes the comment starting at the first `#`, that isn't part of a character or string literal
 pub fn strip_coment(inp: &str) -> &str {
-    if let Some(comment_match) = COMMENT_RE.captures(inp) {
-        return &inp[..comment_match.get(0).unwrap().start()];
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in inp.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '#' => return &inp[..index],
+            None => {}
+        }
     }
 
     inp
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_keep_hashes() {
+        assert_eq!(strip_coment("nop # comment"), "nop ");
+        assert_eq!(
+            strip_coment(r#".ascii "a \" # b" # comment"#),
+            r#".ascii "a \" # b" "#
+        );
+        assert_eq!(strip_coment("print '#' # comment"), "print '#' ");
+    }
+}
@@ -0,0 +1,400 @@
+use std::{error::Error, fmt::Write, path::Path};
+
+use crate::{
+    directive::{DirectiveParseError, DIRECTIVES},
+    instruction::{InstructionParseError, MNEMONICS},
+    label_ref::LabelRefParseError,
+    register::{RegisterParseError, REGISTERS},
+    span::Span,
+    suggest::closest,
+    ParseError, ParseWarning,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+
+    /// The ANSI color of the severity and the underline
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Error => "1;31",
+            Severity::Warning => "1;33",
+        }
+    }
+}
+
+/// A source file diagnostics point into
+#[derive(Debug, Clone, Copy)]
+pub struct Source<'a> {
+    pub path: &'a Path,
+    pub text: &'a str,
+}
+
+/// An error or warning about a program, optionally pointing at the part of the source it is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Option<Span>,
+    /// Explains the underlined span
+    pub label: Option<String>,
+    pub notes: Vec<String>,
+    /// A proposed fix, like a "did you mean" suggestion
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Severity::Error, message.into())
+    }
+
+    pub fn warning(message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Severity::Warning, message.into())
+    }
+
+    fn new(severity: Severity, message: String) -> Diagnostic {
+        Diagnostic {
+            severity,
+            message,
+            span: None,
+            label: None,
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn with_span(mut self, span: Span, label: impl Into<String>) -> Diagnostic {
+        self.span = Some(span);
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Diagnostic {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: Option<String>) -> Diagnostic {
+        self.help = help;
+        self
+    }
+
+    /// Renders the diagnostic for a terminal, showing the source line with the span underlined.
+    ///
+    /// `colored` adds ANSI escape codes.
+    pub fn render(&self, source: Option<Source>, colored: bool) -> String {
+        let paint = |color: &str, text: &str| {
+            if colored {
+                format!("\x1b[{}m{}\x1b[0m", color, text)
+            } else {
+                text.to_string()
+            }
+        };
+        let mut ret = format!(
+            "{}{}",
+            paint(self.severity.color(), self.severity.name()),
+            paint("1", &format!(": {}", self.message))
+        );
+
+        let line = self.span.and_then(|span| {
+            let text = source?.text;
+            let line = text.split('\n').nth(span.line)?;
+            let underlined = text.get(span.start..span.end)?.split('\n').next()?;
+            Some((span, line.trim_end_matches('\r'), underlined))
+        });
+        let gutter = line.map_or(0, |(span, ..)| (span.line + 1).to_string().len());
+        let arrow = paint("1;34", &format!("{:gutter$}-->", ""));
+        match (source, self.span) {
+            (Some(source), Some(span)) => {
+                let _ = write!(ret, "\n{} {}:{}", arrow, source.path.display(), span);
+            }
+            (Some(source), None) => {
+                let _ = write!(ret, "\n{} {}", arrow, source.path.display());
+            }
+            (None, Some(span)) => {
+                let _ = write!(ret, "\n{} {}", arrow, span);
+            }
+            (None, None) => {}
+        }
+
+        let bar = paint("1;34", &format!("{:gutter$} |", ""));
+        if let Some((span, line, underlined)) = line {
+            let width =
+                |text: &str| -> usize { text.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum() };
+            let before: String = line.chars().take(span.column).collect();
+            let carets = "^".repeat(width(underlined).max(1));
+            let _ = write!(
+                ret,
+                "\n{}\n{} {}\n{} {:padding$}{}",
+                bar,
+                paint("1;34", &format!("{} |", span.line + 1)),
+                line.replace('\t', "    "),
+                bar,
+                "",
+                paint(
+                    self.severity.color(),
+                    format!("{} {}", carets, self.label.as_deref().unwrap_or_default()).trim_end()
+                ),
+                padding = width(&before),
+            );
+        }
+        for note in &self.notes {
+            let _ = write!(ret, "\n{:gutter$} = {}: {}", "", paint("1", "note"), note);
+        }
+        if let Some(help) = &self.help {
+            let _ = write!(ret, "\n{:gutter$} = {}: {}", "", paint("1", "help"), help);
+        }
+        ret
+    }
+
+    /// Renders the diagnostic as a single line JSON object for tools.
+    ///
+    /// Lines and columns start at 1, `start` and `end` are byte offsets into the source.
+    pub fn to_json(&self, path: Option<&Path>) -> String {
+        let optional = |s: Option<&str>| s.map_or("null".to_string(), json_string);
+        let number = |n: Option<usize>| n.map_or("null".to_string(), |n| n.to_string());
+        let notes: Vec<_> = self.notes.iter().map(|n| json_string(n)).collect();
+        format!(
+            "{{\"severity\":{},\"message\":{},\"file\":{},\"line\":{},\"column\":{},\"start\":{},\"end\":{},\"label\":{},\"notes\":[{}],\"help\":{}}}",
+            json_string(self.severity.name()),
+            json_string(&self.message),
+            optional(path.map(|p| p.to_string_lossy()).as_deref()),
+            number(self.span.map(|s| s.line + 1)),
+            number(self.span.map(|s| s.column + 1)),
+            number(self.span.map(|s| s.start)),
+            number(self.span.map(|s| s.end)),
+            optional(self.label.as_deref()),
+            notes.join(","),
+            optional(self.help.as_deref()),
+        )
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len() + 2);
+    ret.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\t' => ret.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(ret, "\\u{:04x}", c as u32);
+            }
+            c => ret.push(c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+/// Joins the error with its sources, like `parsing register failed: the register ... is invalid`
+pub fn error_chain(error: &dyn Error) -> String {
+    let mut ret = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let _ = write!(ret, ": {}", cause);
+        source = cause.source();
+    }
+    ret
+}
+
+fn did_you_mean(suggestion: Option<&str>) -> Option<String> {
+    suggestion.map(|s| format!("did you mean `{}`?", s))
+}
+
+fn register_help(register: &str) -> Option<String> {
+    let general = register
+        .strip_prefix("$G_")
+        .and_then(|n| n.parse::<u64>().ok());
+    if general.is_some_and(|n| n > 7) {
+        return Some("the general purpose registers are `$G_0` to `$G_7`".to_string());
+    }
+    did_you_mean(closest(register, REGISTERS.iter().copied()))
+}
+
+impl ParseError {
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self {
+            ParseError::InstructionParseError(e, span, _) => instruction_diagnostic(e, *span),
+            ParseError::DirectiveParseError(e, span, _) => directive_diagnostic(e, *span),
+            ParseError::DuplicateLabel(name, span, first) => {
+                Diagnostic::error(format!("label `{}` is defined more than once", name))
+                    .with_span(*span, "redefined here")
+                    .with_note(format!(
+                        "first defined at `{}`, references resolve to that definition",
+                        first
+                    ))
+            }
+        }
+    }
+}
+
+impl ParseWarning {
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self {
+            ParseWarning::UnusedLabel(_, span) => {
+                Diagnostic::warning(self.to_string()).with_span(*span, "never referenced")
+            }
+            ParseWarning::CaseMismatch(_, other, span) => Diagnostic::warning(self.to_string())
+                .with_span(*span, "labels are case sensitive")
+                .with_help(did_you_mean(Some(&format!(":{}", other)))),
+        }
+    }
+}
+
+fn instruction_diagnostic(error: &InstructionParseError, span: Span) -> Diagnostic {
+    let diagnostic = Diagnostic::error(error_chain(error));
+    match error {
+        InstructionParseError::RegisterParseError(RegisterParseError::InvalidFormatted(r)) => {
+            diagnostic
+                .with_span(span, "not a register")
+                .with_help(register_help(r))
+        }
+        InstructionParseError::LiteralParseError(_) => {
+            diagnostic.with_span(span, "invalid literal")
+        }
+        InstructionParseError::RegisterLiteralParseError(
+            RegisterParseError::InvalidFormatted(operand),
+            literal,
+        ) => {
+            let diagnostic = Diagnostic::error(format!(
+                "operand `{}` is neither a register nor a literal",
+                operand
+            ))
+            .with_span(span, "expected a register or a literal");
+            if operand.starts_with('$') {
+                diagnostic.with_help(register_help(operand))
+            } else {
+                diagnostic.with_note(format!("as a literal: {}", literal))
+            }
+        }
+        InstructionParseError::LabelRefParseError(LabelRefParseError::InvalidFormatted(r)) => {
+            diagnostic
+                .with_span(span, "expected a label reference")
+                .with_note("label references look like `:name`, `:.local` or `:1b`")
+                .with_help(did_you_mean(
+                    (!r.starts_with(':')).then(|| format!(":{}", r)).as_deref(),
+                ))
+        }
+        InstructionParseError::UnknownInstruction(instruction) => {
+            let mnemonic = instruction.split_whitespace().next().unwrap_or_default();
+            let mnemonics = MNEMONICS.iter().map(|(m, _)| *m);
+            Diagnostic::error(format!("instruction `{}` is unknown", mnemonic))
+                .with_span(span, "unknown instruction")
+                .with_help(did_you_mean(closest(mnemonic, mnemonics)))
+        }
+        InstructionParseError::OperandCount(_, expected, _) => {
+            diagnostic.with_span(span, format!("expects {} operand(s)", expected))
+        }
+        InstructionParseError::UnknownLabel(label, suggestion) => {
+            let diagnostic = diagnostic.with_span(span, "undefined label");
+            match suggestion {
+                Some(s) if s.eq_ignore_ascii_case(label) => diagnostic
+                    .with_note("labels are case sensitive")
+                    .with_help(did_you_mean(Some(&format!(":{}", s)))),
+                _ => diagnostic.with_help(did_you_mean(
+                    suggestion.as_ref().map(|s| format!(":{}", s)).as_deref(),
+                )),
+            }
+        }
+        InstructionParseError::OutsideTextSection(_) => diagnostic
+            .with_span(span, "instruction in the data section")
+            .with_help(Some("add `.text` before the instruction".to_string())),
+    }
+}
+
+fn directive_diagnostic(error: &DirectiveParseError, span: Span) -> Diagnostic {
+    let diagnostic = Diagnostic::error(error_chain(error));
+    match error {
+        DirectiveParseError::UnknownDirective(name) => diagnostic
+            .with_span(span, "unknown directive")
+            .with_help(did_you_mean(closest(name, DIRECTIVES.iter().copied()))),
+        DirectiveParseError::OutsideDataSection(_) => diagnostic
+            .with_span(span, "data in the text section")
+            .with_help(Some("add `.data` before the directive".to_string())),
+        DirectiveParseError::UndefinedGlobal(_) => {
+            diagnostic.with_span(span, "not defined in this file")
+        }
+        DirectiveParseError::DefinedExtern(_) => {
+            diagnostic.with_span(span, "declared as defined in another file")
+        }
+        _ => diagnostic.with_span(span, "invalid directive"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_all;
+
+    fn diagnostics(source: &str) -> Vec<Diagnostic> {
+        parse_all(source)
+            .errors
+            .iter()
+            .map(ParseError::diagnostic)
+            .collect()
+    }
+
+    #[test]
+    fn suggestions() {
+        let source = "main:\n\tnotii $G_0\n\tmov $g_0 1\n\tpush $G_8\n\tjmp :mian\n\t.wrod 1\n";
+        let help: Vec<_> = diagnostics(source)
+            .into_iter()
+            .map(|d| d.help.unwrap_or_default())
+            .collect();
+
+        assert_eq!(
+            help,
+            [
+                "did you mean `noti`?",
+                "did you mean `$G_0`?",
+                "the general purpose registers are `$G_0` to `$G_7`",
+                "did you mean `:main`?",
+                "did you mean `.word`?"
+            ]
+        );
+    }
+
+    #[test]
+    fn rendering() {
+        let source = "main:\n\texit $g_1 # comment\n";
+        let diagnostic = &diagnostics(source)[0];
+        let source = Source {
+            path: Path::new("main.edu"),
+            text: source,
+        };
+
+        assert_eq!(
+            diagnostic.render(Some(source), false),
+            "error: operand `$g_1` is neither a register nor a literal
+ --> main.edu:2:7
+  |
+2 |     exit $g_1 # comment
+  |          ^^^^ expected a register or a literal
+  = help: did you mean `$G_1`?"
+        );
+        assert!(diagnostic
+            .render(Some(source), true)
+            .starts_with("\x1b[1;31merror\x1b[0m"));
+        assert_eq!(
+            diagnostic.to_json(Some(source.path)),
+            "{\"severity\":\"error\",\"message\":\"operand `$g_1` is neither a register nor a literal\",\"file\":\"main.edu\",\"line\":2,\"column\":7,\"start\":12,\"end\":16,\"label\":\"expected a register or a literal\",\"notes\":[],\"help\":\"did you mean `$G_1`?\"}"
+        );
+        assert_eq!(
+            Diagnostic::warning("a \"quote\"").to_json(None),
+            "{\"severity\":\"warning\",\"message\":\"a \\\"quote\\\"\",\"file\":null,\"line\":null,\"column\":null,\"start\":null,\"end\":null,\"label\":null,\"notes\":[],\"help\":null}"
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
+use crate::{
+    label::LabelToken,
+    literal::{
+        escape_string_literal, parse_string_literal, split_operands, LiteralParseError,
+        LiteralToken,
+    },
+};
+
+/// The section the following elements are placed in
+#[derive(Copy, Clone, Hash, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Section {
+    /// Instructions, placed in the code section
+    #[default]
+    Text,
+    /// Initialized data, placed in the data section after the code
+    Data,
+}
+
+/// The names of all directives
+pub const DIRECTIVES: &[&str] = &[
+    ".text", ".data", ".word", ".byte", ".ascii", ".asciz", ".zero", ".align", ".global", ".extern",
+];
+
+/// The largest data section in bytes, which bounds `.zero` and the sum of all data directives
+pub const MAX_DATA_SIZE: u64 = 1 << 24;
+
+/// The largest alignment of `.align`
+pub const MAX_ALIGNMENT: u64 = 1 << 16;
+
+/// An assembler directive, a line starting with `.`
+#[derive(Clone, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Directive {
+    /// `.text` or `.data`, switches the section
+    Section(Section),
+    /// `.word`, 64-bit little endian values
+    Word(Vec<LiteralToken>),
+    /// `.byte`, single bytes
+    Byte(Vec<LiteralToken>),
+    /// `.ascii`, the bytes of a string
+    Ascii(Vec<u8>),
+    /// `.asciz`, the bytes of a string followed by a zero byte
+    Asciz(Vec<u8>),
+    /// `.zero`, the given number of zero bytes
+    Zero(u64),
+    /// `.align`, zero bytes up to the next address divisible by the given power of two
+    Align(u64),
+    /// `.global`, makes a label of this file visible to the other linked files
+    Global(String),
+    /// `.extern`, declares a label defined in another linked file
+    Extern(String),
+}
+
+#[derive(Debug, Error)]
+pub enum DirectiveParseError {
+    #[error("directive `{0}` is unknown")]
+    UnknownDirective(String),
+    #[error("parsing literal failed")]
+    LiteralParseError(#[from] LiteralParseError),
+    #[error("directive `{0}` expects {1}")]
+    InvalidOperands(String, &'static str),
+    #[error("value `{0}` doesn't fit into a byte")]
+    ByteOutOfRange(LiteralToken),
+    #[error("alignment `{0}` isn't a power of two")]
+    InvalidAlignment(u64),
+    #[error("alignment `{0}` is larger than the maximum of `{max}`", max = MAX_ALIGNMENT)]
+    AlignmentTooLarge(u64),
+    #[error("data section of `{0}` bytes is larger than the maximum of `{max}` bytes", max = MAX_DATA_SIZE)]
+    DataTooLarge(u64),
+    #[error("directive `{0}` has to be placed in the data section")]
+    OutsideDataSection(String),
+    #[error("label `{0}` is declared `.global`, but not defined")]
+    UndefinedGlobal(String),
+    #[error("label `{0}` is declared `.extern`, but defined in this file")]
+    DefinedExtern(String),
+}
+
+impl Directive {
+    /// Returns whether the directive emits data, which is only allowed in the data section
+    pub fn is_data(&self) -> bool {
+        !matches!(
+            self,
+            Directive::Section(_) | Directive::Global(_) | Directive::Extern(_)
+        )
+    }
+
+    /// Number of bytes the directive occupies when placed at `address`
+    pub fn size_at(&self, address: u64) -> u64 {
+        match self {
+            Directive::Section(_) | Directive::Global(_) | Directive::Extern(_) => 0,
+            Directive::Word(values) => values.len() as u64 * 8,
+            Directive::Byte(values) => values.len() as u64,
+            Directive::Ascii(bytes) => bytes.len() as u64,
+            Directive::Asciz(bytes) => bytes.len() as u64 + 1,
+            Directive::Zero(count) => *count,
+            Directive::Align(alignment) => address.next_multiple_of(*alignment) - address,
+        }
+    }
+}
+
+impl FromStr for Directive {
+    type Err = DirectiveParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, operands) = match s.split_once(char::is_whitespace) {
+            Some((name, operands)) => (name, operands.trim()),
+            None => (s, ""),
+        };
+        let invalid = |expected| DirectiveParseError::InvalidOperands(name.to_string(), expected);
+
+        match name {
+            ".text" | ".data" if !operands.is_empty() => Err(invalid("no operands")),
+            ".text" => Ok(Directive::Section(Section::Text)),
+            ".data" => Ok(Directive::Section(Section::Data)),
+            ".word" => parse_literals(operands)
+                .and_then(|v| non_empty(v, invalid("at least one value")))
+                .map(Directive::Word),
+            ".byte" => {
+                let values = non_empty(parse_literals(operands)?, invalid("at least one value"))?;
+                if let Some(value) = values.iter().find(|v| byte_value(v).is_none()) {
+                    return Err(DirectiveParseError::ByteOutOfRange(*value));
+                }
+                Ok(Directive::Byte(values))
+            }
+            ".ascii" => Ok(Directive::Ascii(parse_string_literal(operands)?)),
+            ".asciz" => Ok(Directive::Asciz(parse_string_literal(operands)?)),
+            ".zero" => {
+                let count = parse_count(operands).ok_or(invalid("an unsigned size"))?;
+                if count > MAX_DATA_SIZE {
+                    return Err(DirectiveParseError::DataTooLarge(count));
+                }
+                Ok(Directive::Zero(count))
+            }
+            ".align" => {
+                let alignment = parse_count(operands).ok_or(invalid("an unsigned alignment"))?;
+                if !alignment.is_power_of_two() {
+                    return Err(DirectiveParseError::InvalidAlignment(alignment));
+                }
+                if alignment > MAX_ALIGNMENT {
+                    return Err(DirectiveParseError::AlignmentTooLarge(alignment));
+                }
+                Ok(Directive::Align(alignment))
+            }
+            ".global" => parse_symbol(operands)
+                .ok_or(invalid("a global label name"))
+                .map(Directive::Global),
+            ".extern" => parse_symbol(operands)
+                .ok_or(invalid("a global label name"))
+                .map(Directive::Extern),
+            _ => Err(DirectiveParseError::UnknownDirective(name.to_string())),
+        }
+    }
+}
+
+impl Display for Directive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |values: &[LiteralToken]| {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        match self {
+            Directive::Section(Section::Text) => write!(f, ".text"),
+            Directive::Section(Section::Data) => write!(f, ".data"),
+            Directive::Word(values) => write!(f, ".word {}", join(values)),
+            Directive::Byte(values) => write!(f, ".byte {}", join(values)),
+            Directive::Ascii(bytes) => write!(f, ".ascii {}", escape_string_literal(bytes)),
+            Directive::Asciz(bytes) => write!(f, ".asciz {}", escape_string_literal(bytes)),
+            Directive::Zero(count) => write!(f, ".zero {}", count),
+            Directive::Align(alignment) => write!(f, ".align {}", alignment),
+            Directive::Global(name) => write!(f, ".global {}", name),
+            Directive::Extern(name) => write!(f, ".extern {}", name),
+        }
+    }
+}
+
+/// Returns the byte `value` is stored as, negative values are stored as two's complement
+pub fn byte_value(value: &LiteralToken) -> Option<u8> {
+    match *value {
+        LiteralToken::Signed(v) => i8::try_from(v)
+            .map(|b| b as u8)
+            .or_else(|_| u8::try_from(v))
+            .ok(),
+        LiteralToken::Unsigned(v) => u8::try_from(v).ok(),
+    }
+}
+
+fn non_empty<T>(values: Vec<T>, error: DirectiveParseError) -> Result<Vec<T>, DirectiveParseError> {
+    if values.is_empty() {
+        return Err(error);
+    }
+    Ok(values)
+}
+
+fn parse_literals(operands: &str) -> Result<Vec<LiteralToken>, DirectiveParseError> {
+    split_operands(operands, |c| c == ',' || c.is_whitespace())
+        .into_iter()
+        .map(|o| LiteralToken::from_str(o).map_err(DirectiveParseError::from))
+        .collect()
+}
+
+fn parse_count(operands: &str) -> Option<u64> {
+    match LiteralToken::from_str(operands).ok()? {
+        LiteralToken::Signed(v) => u64::try_from(v).ok(),
+        LiteralToken::Unsigned(v) => Some(v),
+    }
+}
+
+/// Parses the name of a global label, local and numeric labels can't be shared between files
+fn parse_symbol(operands: &str) -> Option<String> {
+    let label = LabelToken::from_str(&format!("{}:", operands)).ok()?;
+    (!label.is_local() && !label.is_numeric()).then_some(label.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_directives() {
+        assert_eq!(
+            Directive::from_str(".word 1, -2 3u").unwrap(),
+            Directive::Word(vec![
+                LiteralToken::Signed(1),
+                LiteralToken::Signed(-2),
+                LiteralToken::Unsigned(3)
+            ])
+        );
+        assert_eq!(
+            Directive::from_str(r#".asciz "a \"b\"\n""#).unwrap(),
+            Directive::Asciz(b"a \"b\"\n".to_vec())
+        );
+        assert_eq!(
+            Directive::from_str(".data").unwrap(),
+            Directive::Section(Section::Data)
+        );
+        assert!(matches!(
+            Directive::from_str(".byte 256"),
+            Err(DirectiveParseError::ByteOutOfRange(_))
+        ));
+        assert!(matches!(
+            Directive::from_str(".align 3"),
+            Err(DirectiveParseError::InvalidAlignment(3))
+        ));
+        assert!(matches!(
+            Directive::from_str(".align 0x4000000000000000"),
+            Err(DirectiveParseError::AlignmentTooLarge(0x4000000000000000))
+        ));
+        assert!(matches!(
+            Directive::from_str(".zero 0xFFFFFFFFFFFFu"),
+            Err(DirectiveParseError::DataTooLarge(0xFFFFFFFFFFFF))
+        ));
+        assert!(matches!(
+            Directive::from_str(".ascii \"a\"b\""),
+            Err(DirectiveParseError::LiteralParseError(
+                LiteralParseError::InvalidString(_)
+            ))
+        ));
+        assert_eq!(
+            Directive::from_str(".byte 'a', ',' 0x7f").unwrap(),
+            Directive::Byte(vec![
+                LiteralToken::Signed(97),
+                LiteralToken::Signed(44),
+                LiteralToken::Signed(127)
+            ])
+        );
+        assert_eq!(
+            Directive::from_str(".global print.char").unwrap(),
+            Directive::Global("print.char".to_string())
+        );
+        for source in [".extern .loop", ".extern 1", ".global a b", ".global"] {
+            assert!(
+                matches!(
+                    Directive::from_str(source),
+                    Err(DirectiveParseError::InvalidOperands(..))
+                ),
+                "{}",
+                source
+            );
+        }
+        assert!(matches!(
+            Directive::from_str(".quad 1"),
+            Err(DirectiveParseError::UnknownDirective(_))
+        ));
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for source in [
+            ".text",
+            ".byte -1 255",
+            ".ascii \"tab\\t\\\"quoted\\\"\\\\\"",
+            ".zero 12",
+            ".align 8",
+            ".extern _exit",
+        ] {
+            assert_eq!(Directive::from_str(source).unwrap().to_string(), source);
+        }
+    }
+
+    #[test]
+    fn size() {
+        assert_eq!(Directive::Align(8).size_at(9), 7);
+        assert_eq!(Directive::Align(8).size_at(16), 0);
+        assert_eq!(Directive::Asciz(b"hi".to_vec()).size_at(3), 3);
+    }
+}
@@ -1,15 +1,89 @@
-use std::str::FromStr;
+use std::fmt::Display;
 use std::rc::Rc;
+use std::str::FromStr;
 
 use thiserror::Error;
 
 use crate::{
     label::LocAwLabel,
     label_ref::{LabelRefParseError, LabelRefToken},
-    literal::{LiteralParseError, LiteralToken},
+    literal::{split_operands, LiteralParseError, LiteralToken},
     register::{RegisterParseError, RegisterToken},
 };
 
+/// Every mnemonic with the number of operands it takes
+pub const MNEMONICS: &[(&str, usize)] = &[
+    ("addts", 3),
+    ("addis", 2),
+    ("addtu", 3),
+    ("addiu", 2),
+    ("subts", 3),
+    ("subis", 2),
+    ("subtu", 3),
+    ("subiu", 2),
+    ("lshlt", 3),
+    ("lshli", 2),
+    ("lshrt", 3),
+    ("lshri", 2),
+    ("ashrt", 3),
+    ("ashri", 2),
+    ("andt", 3),
+    ("andi", 2),
+    ("ort", 3),
+    ("ori", 2),
+    ("xort", 3),
+    ("xori", 2),
+    ("nott", 2),
+    ("noti", 1),
+    ("noi", 1),
+    ("mults_e", 3),
+    ("mulis_e", 2),
+    ("multu_e", 3),
+    ("muliu_e", 2),
+    ("divts_e", 4),
+    ("divtu_e", 4),
+    ("jmp", 1),
+    ("jmpr", 1),
+    ("jmpeq", 3),
+    ("jmpne", 3),
+    ("jmplts", 3),
+    ("jmpltu", 3),
+    ("jmples", 3),
+    ("jmpleu", 3),
+    ("jmpgts", 3),
+    ("jmpgtu", 3),
+    ("jmpges", 3),
+    ("jmpgeu", 3),
+    ("cal", 1),
+    ("calr", 1),
+    ("ret", 1),
+    ("mov", 2),
+    ("load", 2),
+    ("loado", 3),
+    ("loadb", 2),
+    ("loadbo", 3),
+    ("stor", 2),
+    ("storo", 3),
+    ("storb", 2),
+    ("storbo", 3),
+    ("push", 1),
+    ("pop", 1),
+    ("halt", 0),
+    ("exit", 1),
+    ("print", 1),
+    ("read", 1),
+    ("dump", 0),
+    ("nop", 0),
+];
+
+/// Returns the number of operands `mnemonic` takes, if it is an instruction
+pub fn operand_count(mnemonic: &str) -> Option<usize> {
+    MNEMONICS
+        .iter()
+        .find(|(m, _)| *m == mnemonic)
+        .map(|(_, count)| *count)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Instruction {
     ArithmeticBase(ArithmeticBase),
@@ -21,6 +95,46 @@ pub enum Instruction {
     Misc(Misc),
 }
 
+impl Instruction {
+    /// Returns the label the instruction refers to, if any
+    pub fn get_label(&self) -> Option<&LabelRefToken> {
+        match self {
+            Instruction::ControlFlow(i) => i.get_label(),
+            Instruction::Memory(i) => i.get_label(),
+            _ => None,
+        }
+    }
+
+    pub fn get_label_mut(&mut self) -> Option<&mut LabelRefToken> {
+        match self {
+            Instruction::ControlFlow(i) => i.get_label_mut(),
+            Instruction::Memory(i) => i.get_label_mut(),
+            _ => None,
+        }
+    }
+
+    /// Resolves the label the instruction refers to
+    pub fn hydrate(&mut self, loc_label: Rc<LocAwLabel>) {
+        if let Some(label) = self.get_label_mut() {
+            label.label = Some(loc_label);
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::ArithmeticBase(i) => i.fmt(f),
+            Instruction::ArithmeticMultDivEasy(i) => i.fmt(f),
+            Instruction::ArithmeticShift(i) => i.fmt(f),
+            Instruction::ArithmeticBitLogic(i) => i.fmt(f),
+            Instruction::ControlFlow(i) => i.fmt(f),
+            Instruction::Memory(i) => i.fmt(f),
+            Instruction::Misc(i) => i.fmt(f),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum InstructionParseError {
     #[error("parsing register failed")]
@@ -33,14 +147,35 @@ pub enum InstructionParseError {
     LabelRefParseError(#[from] LabelRefParseError),
     #[error("instruction `{0}` is unknown")]
     UnknownInstruction(String),
-    #[error("label `{0}` in instruction `{1}` not found")]
-    UnknownLabel(String, String),
+    #[error("instruction `{0}` expects {1} operand(s), found {2}")]
+    OperandCount(String, usize, usize),
+    /// The label and the defined label it is most likely a typo of
+    #[error("label `{0}` is not defined")]
+    UnknownLabel(String, Option<String>),
+    #[error("instruction `{0}` has to be placed in the text section")]
+    OutsideTextSection(String),
 }
 
 impl InstructionParseError {
     pub(crate) fn is_unknown_instruction(&self) -> bool {
         matches!(self, InstructionParseError::UnknownInstruction(_))
     }
+
+    /// Returns the operand as written, if the error is caused by a single operand
+    pub fn operand(&self) -> Option<String> {
+        match self {
+            InstructionParseError::RegisterParseError(RegisterParseError::InvalidFormatted(s))
+            | InstructionParseError::RegisterLiteralParseError(
+                RegisterParseError::InvalidFormatted(s),
+                _,
+            )
+            | InstructionParseError::LabelRefParseError(LabelRefParseError::InvalidFormatted(s)) => {
+                Some(s.clone())
+            }
+            InstructionParseError::UnknownLabel(label, ..) => Some(format!(":{}", label)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -62,6 +197,15 @@ impl FromStr for RegisterOrLiteral {
     }
 }
 
+impl Display for RegisterOrLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegisterOrLiteral::Register(r) => r.fmt(f),
+            RegisterOrLiteral::Literal(l) => l.fmt(f),
+        }
+    }
+}
+
 impl From<RegisterToken> for RegisterOrLiteral {
     fn from(r: RegisterToken) -> Self {
         RegisterOrLiteral::Register(r)
@@ -116,7 +260,7 @@ pub enum ArithmeticBase {
 
 #[inline]
 fn split_collect(inp: &str) -> Vec<&str> {
-    inp.split_whitespace().collect()
+    split_operands(inp, char::is_whitespace)
 }
 
 impl FromStr for ArithmeticBase {
@@ -175,6 +319,21 @@ impl FromStr for ArithmeticBase {
     }
 }
 
+impl Display for ArithmeticBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticBase::AddTs { d, s, t } => write!(f, "addts {} {} {}", d, s, t),
+            ArithmeticBase::AddIs { s, t } => write!(f, "addis {} {}", s, t),
+            ArithmeticBase::AddTu { d, s, t } => write!(f, "addtu {} {} {}", d, s, t),
+            ArithmeticBase::AddIu { s, t } => write!(f, "addiu {} {}", s, t),
+            ArithmeticBase::SubTs { d, s, t } => write!(f, "subts {} {} {}", d, s, t),
+            ArithmeticBase::SubIs { s, t } => write!(f, "subis {} {}", s, t),
+            ArithmeticBase::SubTu { d, s, t } => write!(f, "subtu {} {} {}", d, s, t),
+            ArithmeticBase::SubIu { s, t } => write!(f, "subiu {} {}", s, t),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ArithmeticShift {
     LshLT {
@@ -251,6 +410,19 @@ impl FromStr for ArithmeticShift {
     }
 }
 
+impl Display for ArithmeticShift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticShift::LshLT { d, s, t } => write!(f, "lshlt {} {} {}", d, s, t),
+            ArithmeticShift::LshLI { s, t } => write!(f, "lshli {} {}", s, t),
+            ArithmeticShift::LshRT { d, s, t } => write!(f, "lshrt {} {} {}", d, s, t),
+            ArithmeticShift::LshRI { s, t } => write!(f, "lshri {} {}", s, t),
+            ArithmeticShift::AshRT { d, s, t } => write!(f, "ashrt {} {} {}", d, s, t),
+            ArithmeticShift::AshRI { s, t } => write!(f, "ashri {} {}", s, t),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ArithmeticBitLogic {
     AndT {
@@ -334,7 +506,7 @@ impl FromStr for ArithmeticBitLogic {
                 let s = RegisterOrLiteral::from_str(s_str)?;
                 Ok(ArithmeticBitLogic::NotT { d, s })
             }
-            ["noi", s_str] => {
+            ["noti" | "noi", s_str] => {
                 let s = RegisterToken::from_str(s_str)?;
                 Ok(ArithmeticBitLogic::NoI { s })
             }
@@ -343,6 +515,21 @@ impl FromStr for ArithmeticBitLogic {
     }
 }
 
+impl Display for ArithmeticBitLogic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticBitLogic::AndT { d, s, t } => write!(f, "andt {} {} {}", d, s, t),
+            ArithmeticBitLogic::AndI { s, t } => write!(f, "andi {} {}", s, t),
+            ArithmeticBitLogic::OrT { d, s, t } => write!(f, "ort {} {} {}", d, s, t),
+            ArithmeticBitLogic::OrI { s, t } => write!(f, "ori {} {}", s, t),
+            ArithmeticBitLogic::XorT { d, s, t } => write!(f, "xort {} {} {}", d, s, t),
+            ArithmeticBitLogic::XorI { s, t } => write!(f, "xori {} {}", s, t),
+            ArithmeticBitLogic::NotT { d, s } => write!(f, "nott {} {}", d, s),
+            ArithmeticBitLogic::NoI { s } => write!(f, "noti {}", s),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ArithmeticMultDivEasy {
     MulTsE {
@@ -425,64 +612,87 @@ impl FromStr for ArithmeticMultDivEasy {
     }
 }
 
+impl Display for ArithmeticMultDivEasy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticMultDivEasy::MulTsE { d, s, t } => write!(f, "mults_e {} {} {}", d, s, t),
+            ArithmeticMultDivEasy::MulIsE { s, t } => write!(f, "mulis_e {} {}", s, t),
+            ArithmeticMultDivEasy::MulTuE { d, s, t } => write!(f, "multu_e {} {} {}", d, s, t),
+            ArithmeticMultDivEasy::MulIuE { s, t } => write!(f, "muliu_e {} {}", s, t),
+            ArithmeticMultDivEasy::DivTsE { d, r, s, t } => {
+                write!(f, "divts_e {} {} {} {}", d, r, s, t)
+            }
+            ArithmeticMultDivEasy::DivTuE { d, r, s, t } => {
+                write!(f, "divtu_e {} {} {} {}", d, r, s, t)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ControlFlow {
     Jmp {
         label: LabelRefToken,
     },
     JmpEq {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
     JmpNe {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
     JmpGtS {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
     JmpGeS {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
     JmpLtS {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
     JmpLeS {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
     JmpGtU {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
     JmpGeU {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
     JmpLtU {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
     JmpLeU {
-        l: RegisterToken,
-        r: RegisterToken,
+        l: RegisterOrLiteral,
+        r: RegisterOrLiteral,
         label: LabelRefToken,
     },
+    JmpR {
+        s: RegisterToken,
+    },
     Cal {
         label: LabelRefToken,
     },
+    CalR {
+        s: RegisterToken,
+    },
     Ret {
         s: RegisterToken,
     },
@@ -503,7 +713,25 @@ impl ControlFlow {
             ControlFlow::JmpLtU { label, .. } => Some(label),
             ControlFlow::JmpLeU { label, .. } => Some(label),
             ControlFlow::Cal { label, .. } => Some(label),
-            ControlFlow::Ret { .. } => None,
+            ControlFlow::JmpR { .. } | ControlFlow::CalR { .. } | ControlFlow::Ret { .. } => None,
+        }
+    }
+
+    pub fn get_label_mut(&mut self) -> Option<&mut LabelRefToken> {
+        match self {
+            ControlFlow::Jmp { label } => Some(label),
+            ControlFlow::JmpEq { label, .. } => Some(label),
+            ControlFlow::JmpNe { label, .. } => Some(label),
+            ControlFlow::JmpGtS { label, .. } => Some(label),
+            ControlFlow::JmpGeS { label, .. } => Some(label),
+            ControlFlow::JmpLtS { label, .. } => Some(label),
+            ControlFlow::JmpLeS { label, .. } => Some(label),
+            ControlFlow::JmpGtU { label, .. } => Some(label),
+            ControlFlow::JmpGeU { label, .. } => Some(label),
+            ControlFlow::JmpLtU { label, .. } => Some(label),
+            ControlFlow::JmpLeU { label, .. } => Some(label),
+            ControlFlow::Cal { label, .. } => Some(label),
+            ControlFlow::JmpR { .. } | ControlFlow::CalR { .. } | ControlFlow::Ret { .. } => None,
         }
     }
 
@@ -545,7 +773,7 @@ impl ControlFlow {
             ControlFlow::Cal { label, .. } => {
                 label.label = Some(loc_label);
             }
-            ControlFlow::Ret { .. } => {}
+            ControlFlow::JmpR { .. } | ControlFlow::CalR { .. } | ControlFlow::Ret { .. } => {}
         };
     }
 }
@@ -561,69 +789,77 @@ impl FromStr for ControlFlow {
                 Ok(ControlFlow::Jmp { label })
             }
             ["jmpeq", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpEq { l, r, label })
             }
             ["jmpne", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpNe { l, r, label })
             }
             ["jmpgts", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpGtS { l, r, label })
             }
             ["jmpges", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpGeS { l, r, label })
             }
             ["jmplts", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpLtS { l, r, label })
             }
             ["jmples", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpLeS { l, r, label })
             }
             ["jmpgtu", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpGtU { l, r, label })
             }
             ["jmpgeu", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpGeU { l, r, label })
             }
             ["jmpltu", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpLtU { l, r, label })
             }
             ["jmpleu", l_str, r_str, label_str] => {
-                let l = RegisterToken::from_str(l_str)?;
-                let r = RegisterToken::from_str(r_str)?;
+                let l = RegisterOrLiteral::from_str(l_str)?;
+                let r = RegisterOrLiteral::from_str(r_str)?;
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::JmpLeU { l, r, label })
             }
+            ["jmpr", s_str] => {
+                let s = RegisterToken::from_str(s_str)?;
+                Ok(ControlFlow::JmpR { s })
+            }
             ["cal", label_str] => {
                 let label = LabelRefToken::from_str(label_str)?;
                 Ok(ControlFlow::Cal { label })
             }
+            ["calr", s_str] => {
+                let s = RegisterToken::from_str(s_str)?;
+                Ok(ControlFlow::CalR { s })
+            }
             ["ret", s_str] => {
                 let s = RegisterToken::from_str(s_str)?;
                 Ok(ControlFlow::Ret { s })
@@ -633,7 +869,29 @@ impl FromStr for ControlFlow {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+impl Display for ControlFlow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlFlow::Jmp { label } => write!(f, "jmp {}", label),
+            ControlFlow::JmpEq { l, r, label } => write!(f, "jmpeq {} {} {}", l, r, label),
+            ControlFlow::JmpNe { l, r, label } => write!(f, "jmpne {} {} {}", l, r, label),
+            ControlFlow::JmpGtS { l, r, label } => write!(f, "jmpgts {} {} {}", l, r, label),
+            ControlFlow::JmpGeS { l, r, label } => write!(f, "jmpges {} {} {}", l, r, label),
+            ControlFlow::JmpLtS { l, r, label } => write!(f, "jmplts {} {} {}", l, r, label),
+            ControlFlow::JmpLeS { l, r, label } => write!(f, "jmples {} {} {}", l, r, label),
+            ControlFlow::JmpGtU { l, r, label } => write!(f, "jmpgtu {} {} {}", l, r, label),
+            ControlFlow::JmpGeU { l, r, label } => write!(f, "jmpgeu {} {} {}", l, r, label),
+            ControlFlow::JmpLtU { l, r, label } => write!(f, "jmpltu {} {} {}", l, r, label),
+            ControlFlow::JmpLeU { l, r, label } => write!(f, "jmpleu {} {} {}", l, r, label),
+            ControlFlow::JmpR { s } => write!(f, "jmpr {}", s),
+            ControlFlow::Cal { label } => write!(f, "cal {}", label),
+            ControlFlow::CalR { s } => write!(f, "calr {}", s),
+            ControlFlow::Ret { s } => write!(f, "ret {}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Memory {
     Mov {
         /// this is the target register
@@ -641,6 +899,11 @@ pub enum Memory {
         /// this is the source register or literal
         s: RegisterOrLiteral,
     },
+    /// Moves the address of a label into a register
+    MovL {
+        t: RegisterToken,
+        label: LabelRefToken,
+    },
     Load {
         t: RegisterToken,
         s: RegisterOrLiteral,
@@ -685,12 +948,33 @@ pub enum Memory {
     },
 }
 
+impl Memory {
+    pub fn get_label(&self) -> Option<&LabelRefToken> {
+        match self {
+            Memory::MovL { label, .. } => Some(label),
+            _ => None,
+        }
+    }
+
+    pub fn get_label_mut(&mut self) -> Option<&mut LabelRefToken> {
+        match self {
+            Memory::MovL { label, .. } => Some(label),
+            _ => None,
+        }
+    }
+}
+
 impl FromStr for Memory {
     type Err = InstructionParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts = split_collect(s);
         match parts[..] {
+            ["mov", t_str, label_str] if label_str.starts_with(':') => {
+                let t = RegisterToken::from_str(t_str)?;
+                let label = LabelRefToken::from_str(label_str)?;
+                Ok(Memory::MovL { t, label })
+            }
             ["mov", t_str, s_str] => {
                 let t = RegisterToken::from_str(t_str)?;
                 let s = RegisterOrLiteral::from_str(s_str)?;
@@ -753,6 +1037,25 @@ impl FromStr for Memory {
     }
 }
 
+impl Display for Memory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Memory::Mov { t, s } => write!(f, "mov {} {}", t, s),
+            Memory::MovL { t, label } => write!(f, "mov {} {}", t, label),
+            Memory::Load { t, s } => write!(f, "load {} {}", t, s),
+            Memory::LoadO { t, s, o } => write!(f, "loado {} {} {}", t, s, o),
+            Memory::Loadb { t, s } => write!(f, "loadb {} {}", t, s),
+            Memory::LoadbO { t, s, o } => write!(f, "loadbo {} {} {}", t, s, o),
+            Memory::Stor { s, t } => write!(f, "stor {} {}", s, t),
+            Memory::StorO { s, t, o } => write!(f, "storo {} {} {}", s, t, o),
+            Memory::Storb { s, t } => write!(f, "storb {} {}", s, t),
+            Memory::StorbO { s, t, o } => write!(f, "storbo {} {} {}", s, t, o),
+            Memory::Push { d } => write!(f, "push {}", d),
+            Memory::Pop { d } => write!(f, "pop {}", d),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Misc {
     Halt,
@@ -790,3 +1093,16 @@ impl FromStr for Misc {
         }
     }
 }
+
+impl Display for Misc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Misc::Halt => write!(f, "halt"),
+            Misc::Exit { s } => write!(f, "exit {}", s),
+            Misc::Print { s } => write!(f, "print {}", s),
+            Misc::Read { s } => write!(f, "read {}", s),
+            Misc::Dump => write!(f, "dump"),
+            Misc::Nop => write!(f, "nop"),
+        }
+    }
+}
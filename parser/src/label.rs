@@ -1,13 +1,14 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 use lazy_static::lazy_static;
 use regex::Regex;
 use thiserror::Error;
 
-const LABEL_EXP: &str = "([A-Za-z]+):";
+/// A global identifier, local labels prefix it with a `.`, numeric local labels consist of digits
+pub(crate) const IDENT_EXP: &str = r"[A-Za-z_][A-Za-z0-9_.]*";
 
 lazy_static! {
-    static ref LABEL_RE: Regex = Regex::new(LABEL_EXP).unwrap();
+    static ref LABEL_RE: Regex = Regex::new(&format!(r"(\.?{}|[0-9]+):", IDENT_EXP)).unwrap();
 }
 
 #[derive(Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -29,6 +30,24 @@ impl Clone for LabelToken {
     }
 }
 
+impl LabelToken {
+    /// Returns whether the label is local to the preceding global label, like `.loop`
+    pub fn is_local(&self) -> bool {
+        self.content.starts_with('.')
+    }
+
+    /// Returns whether the label is a numeric local label, like `1`, referenced as `1b` or `1f`
+    pub fn is_numeric(&self) -> bool {
+        self.content.starts_with(|c: char| c.is_ascii_digit())
+    }
+}
+
+impl Display for LabelToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:", self.content)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum LabelParseError {
     #[error("the label string `{0}` is invalid")]
@@ -56,6 +75,10 @@ impl FromStr for LabelToken {
     }
 }
 
+/// A label at a location, the name is unique within a program.
+///
+/// Local labels are named after their global label, e.g. `main@.loop`, numeric local labels after
+/// their number and location, e.g. `1@4`.
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct LocAwLabel {
     pub name: String,
@@ -68,3 +91,31 @@ impl LocAwLabel {
         Self { name, loc }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_syntax() {
+        for (s, local, numeric) in [
+            ("loop_1:", false, false),
+            ("_start:", false, false),
+            ("print.char:", false, false),
+            (".loop:", true, false),
+            ("1:", false, true),
+        ] {
+            let label = LabelToken::from_str(s).unwrap();
+            assert_eq!(label.to_string(), s);
+            assert_eq!(
+                (label.is_local(), label.is_numeric()),
+                (local, numeric),
+                "{}",
+                s
+            );
+        }
+        for s in ["1a:", "..a:", "a-b:", "a:b:", ":"] {
+            assert!(LabelToken::from_str(s).is_err(), "{}", s);
+        }
+    }
+}
@@ -1,15 +1,14 @@
-use std::{rc::Rc, str::FromStr};
+use std::{fmt::Display, rc::Rc, str::FromStr};
 
 use lazy_static::lazy_static;
 use regex::Regex;
 use thiserror::Error;
 
-use crate::label::LocAwLabel;
-
-const LABEL_REF_EXP: &str = ":([A-Za-z]+)";
+use crate::label::{LocAwLabel, IDENT_EXP};
 
 lazy_static! {
-    static ref LABEL_REF_RE: Regex = Regex::new(LABEL_REF_EXP).unwrap();
+    static ref LABEL_REF_RE: Regex =
+        Regex::new(&format!(r":(\.?{}|[0-9]+[bf])", IDENT_EXP)).unwrap();
 }
 
 #[derive(Clone, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -44,3 +43,59 @@ impl FromStr for LabelRefToken {
         }
     }
 }
+
+/// The label a [`LabelRefToken`] refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelRefTarget<'a> {
+    /// A global label, like `:main`
+    Global(&'a str),
+    /// A label local to the enclosing global label, like `:.loop`
+    Local(&'a str),
+    /// The closest numeric local label before the reference, like `:1b`
+    Backward(&'a str),
+    /// The closest numeric local label after the reference, like `:1f`
+    Forward(&'a str),
+}
+
+impl LabelRefToken {
+    pub fn target(&self) -> LabelRefTarget<'_> {
+        let content = self.content.as_str();
+        if content.starts_with('.') {
+            return LabelRefTarget::Local(content);
+        }
+        if content.starts_with(|c: char| c.is_ascii_digit()) {
+            let (number, direction) = content.split_at(content.len() - 1);
+            return match direction {
+                "b" => LabelRefTarget::Backward(number),
+                _ => LabelRefTarget::Forward(number),
+            };
+        }
+        LabelRefTarget::Global(content)
+    }
+}
+
+impl Display for LabelRefToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, ":{}", self.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets() {
+        for (s, target) in [
+            (":loop_1", LabelRefTarget::Global("loop_1")),
+            (":print.char", LabelRefTarget::Global("print.char")),
+            (":.loop", LabelRefTarget::Local(".loop")),
+            (":12b", LabelRefTarget::Backward("12")),
+            (":1f", LabelRefTarget::Forward("1")),
+        ] {
+            assert_eq!(LabelRefToken::from_str(s).unwrap().target(), target);
+        }
+        assert!(LabelRefToken::from_str(":1").is_err());
+        assert!(LabelRefToken::from_str(":1x").is_err());
+    }
+}
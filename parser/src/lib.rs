@@ -1,67 +1,244 @@
-use std::{collections::HashMap, rc::Rc, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    str::FromStr,
+};
 
 use comment::strip_coment;
+use directive::{Directive, DirectiveParseError, Section, MAX_DATA_SIZE};
 use instruction::{
-    ArithmeticBase, ArithmeticBitLogic, ArithmeticMultDivEasy, ArithmeticShift, ControlFlow,
-    Instruction, InstructionParseError, Memory, Misc,
+    operand_count, ArithmeticBase, ArithmeticBitLogic, ArithmeticMultDivEasy, ArithmeticShift,
+    ControlFlow, Instruction, InstructionParseError, Memory, Misc,
 };
 use label::{LabelToken, LocAwLabel};
+use label_ref::{LabelRefTarget, LabelRefToken};
+use literal::split_operands;
+use span::{InstructionSpans, Span};
+use suggest::closest;
 use thiserror::Error;
 
 pub mod comment;
+pub mod diagnostic;
+pub mod directive;
 pub mod instruction;
 pub mod label;
 pub mod label_ref;
 pub mod literal;
 pub mod register;
+pub mod span;
+pub mod suggest;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PureElement {
-    Instruction(Instruction),
-    Label(Rc<LocAwLabel>),
+    Instruction(Instruction, InstructionSpans),
+    Label(Rc<LocAwLabel>, Span),
+    Directive(Directive, Span),
 }
 
+pub type LabelMap = HashMap<String, Rc<LocAwLabel>>;
+
+/// The label execution starts at, unless another one is chosen
+pub const DEFAULT_ENTRY: &str = "_start";
+
+/// An error in a line of the source, the span locates the operand causing it or the whole line
 #[derive(Debug, Error)]
 pub enum ParseError {
-    #[error(
-        "while parsing instruction `{2}` at line `{1}`, an instruction parse error occured: `{0}`"
-    )]
-    InstructionParseError(InstructionParseError, usize, String),
+    #[error("while parsing instruction `{2}` at `{1}`, an instruction parse error occured: `{0}`")]
+    InstructionParseError(InstructionParseError, Span, String),
+    #[error("while parsing directive `{2}` at `{1}`, a directive parse error occured: `{0}`")]
+    DirectiveParseError(DirectiveParseError, Span, String),
+    /// The label, its definition and the first definition, which references resolve to
+    #[error("label `{0}` at `{1}` is already defined at `{2}`")]
+    DuplicateLabel(String, Span, Span),
+}
+
+impl ParseError {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::InstructionParseError(_, span, _) => *span,
+            ParseError::DirectiveParseError(_, span, _) => *span,
+            ParseError::DuplicateLabel(_, span, _) => *span,
+        }
+    }
+}
+
+/// A likely mistake, that doesn't prevent assembling the program
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// A label, that is neither referenced, declared `.global` nor the entry point
+    #[error("label `{0}` is never used")]
+    UnusedLabel(String, Span),
+    /// A reference and another label, that only differs in case from the referenced one
+    #[error("label `{0}` only differs in case from the label `{1}`")]
+    CaseMismatch(String, String, Span),
+}
+
+impl ParseWarning {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseWarning::UnusedLabel(_, span) => *span,
+            ParseWarning::CaseMismatch(_, _, span) => *span,
+        }
+    }
+}
+
+/// A source line, that isn't empty without its comment
+struct Line<'a> {
+    /// Index of the line in the source
+    index: usize,
+    /// Byte offset of the line in the source
+    offset: usize,
+    raw: &'a str,
+    /// The line without its comment and surrounding whitespace
+    content: &'a str,
+}
+
+impl Line<'_> {
+    /// Returns the span of `part`, which has to be a slice of the line
+    fn span(&self, part: &str) -> Span {
+        Span::in_line(self.index, self.offset, self.raw, part)
+    }
 }
 
-fn preprocess_input(inp: &str) -> Vec<(usize, &str)> {
+fn preprocess_input(inp: &str) -> Vec<Line<'_>> {
+    let mut offset = 0;
     inp.split('\n')
         .enumerate()
-        .map(|e| (e.0, strip_coment(e.1)))
-        .map(|e| (e.0, e.1.trim()))
-        .filter(|e| !std::primitive::str::is_empty(e.1))
+        .map(|(index, raw)| {
+            let line = Line {
+                index,
+                offset,
+                raw,
+                content: strip_coment(raw).trim(),
+            };
+            offset += raw.len() + 1;
+            line
+        })
+        .filter(|l| !l.content.is_empty())
         .collect()
 }
 
-fn collect_labels(
-    lines: &[(usize, &str)],
-) -> (HashMap<String, Rc<LocAwLabel>>, HashMap<usize, String>) {
-    let mut ret = HashMap::new();
-    let mut ret_set = HashMap::new();
+/// The labels of a program, see [`LocAwLabel`] for how they are named
+#[derive(Default)]
+struct Labels {
+    map: LabelMap,
+    /// The name of the label defined at a clean line index
+    locs: HashMap<usize, String>,
+    /// The clean line indices every numeric label is defined at, in ascending order
+    numeric: HashMap<String, Vec<usize>>,
+    /// The labels declared `.extern`, which are resolved when linking
+    externs: HashSet<String>,
+}
+
+/// The position of an instruction, which determines the labels its local references resolve to
+struct LabelScope<'a> {
+    labels: &'a Labels,
+    /// The name of the preceding global label
+    global: Option<&'a str>,
+    /// The clean line index of the instruction
+    index: usize,
+}
+
+impl LabelScope<'_> {
+    fn resolve(&self, label_ref: &LabelRefToken) -> Option<&Rc<LocAwLabel>> {
+        let numeric = |number| self.labels.numeric.get(number).into_iter().flatten();
+        let name = match label_ref.target() {
+            LabelRefTarget::Global(name) => name.to_string(),
+            LabelRefTarget::Local(name) => local_name(self.global, name),
+            LabelRefTarget::Backward(number) => {
+                let index = numeric(number).rev().find(|i| **i < self.index)?;
+                numeric_name(number, *index)
+            }
+            LabelRefTarget::Forward(number) => {
+                let index = numeric(number).find(|i| **i > self.index)?;
+                numeric_name(number, *index)
+            }
+        };
+        self.labels.map.get(&name)
+    }
+
+    /// Returns whether `label_ref` refers to a label declared `.extern`
+    fn is_extern(&self, label_ref: &LabelRefToken) -> bool {
+        match label_ref.target() {
+            LabelRefTarget::Global(name) => self.labels.externs.contains(name),
+            _ => false,
+        }
+    }
+
+    /// Returns the label `label_ref` is most likely a typo of, written like the reference
+    fn suggest(&self, label_ref: &LabelRefToken) -> Option<String> {
+        let names = (self.labels.map.keys())
+            .chain(&self.labels.externs)
+            .map(String::as_str);
+        match label_ref.target() {
+            LabelRefTarget::Global(name) => {
+                closest(name, names.filter(|n| !n.contains('@'))).map(str::to_string)
+            }
+            LabelRefTarget::Local(name) => {
+                let prefix = local_name(self.global, "");
+                let locals = names
+                    .filter_map(|n| n.strip_prefix(&prefix))
+                    .filter(|n| n.starts_with('.'));
+                closest(name, locals).map(str::to_string)
+            }
+            LabelRefTarget::Backward(_) | LabelRefTarget::Forward(_) => None,
+        }
+    }
+}
+
+/// Names a local label after its global label, with an `@` no global label can contain
+fn local_name(global: Option<&str>, local: &str) -> String {
+    format!("{}@{}", global.unwrap_or_default(), local)
+}
+
+fn numeric_name(number: &str, index: usize) -> String {
+    format!("{}@{}", number, index)
+}
+
+/// Returns the name of a label as written in the source, see [`local_name`] and [`numeric_name`]
+fn source_name(name: &str) -> &str {
+    match name.split_once('@') {
+        Some((_, local)) if local.starts_with('.') => local,
+        Some((number, _)) => number,
+        None => name,
+    }
+}
+
+fn collect_labels(lines: &[Line]) -> Labels {
+    let mut ret = Labels::default();
+    let mut global: Option<String> = None;
     lines.iter().enumerate().for_each(|e| {
         let index = e.0;
-        let line = e.1 .1;
+        let line = e.1.content;
 
+        if let Ok(Directive::Extern(name)) = Directive::from_str(line) {
+            ret.externs.insert(name);
+        }
         if let Ok(d) = LabelToken::from_str(line) {
-            let loc_aw_label = LocAwLabel::new(d.content.clone(), index);
-            let rc_aw_label = Rc::new(loc_aw_label);
-            ret.insert(d.content.clone(), rc_aw_label);
-            ret_set.insert(index, d.content);
+            let name = if d.is_numeric() {
+                ret.numeric
+                    .entry(d.content.clone())
+                    .or_default()
+                    .push(index);
+                numeric_name(&d.content, index)
+            } else if d.is_local() {
+                local_name(global.as_deref(), &d.content)
+            } else {
+                global = Some(d.content.clone());
+                d.content
+            };
+            // references resolve to the first definition, the others are reported by `parse_all`
+            ret.map
+                .entry(name.clone())
+                .or_insert_with(|| Rc::new(LocAwLabel::new(name.clone(), index)));
+            ret.locs.insert(index, name);
         }
     });
 
-    (ret, ret_set)
+    ret
 }
 
-fn parse_instruction(
-    inp: &str,
-    labels: &HashMap<String, Rc<LocAwLabel>>,
-) -> Result<Instruction, InstructionParseError> {
+fn parse_instruction(inp: &str, labels: &LabelScope) -> Result<Instruction, InstructionParseError> {
     let arithmetic_base_result = ArithmeticBase::from_str(inp).map(Instruction::ArithmeticBase);
     if arithmetic_base_result.is_ok() {
         return arithmetic_base_result;
@@ -97,75 +274,274 @@ fn parse_instruction(
         return Err(arithmetic_bitlogic_error);
     }
     let control_flow_result = ControlFlow::from_str(inp);
-    if let Ok(mut control_flow_instruction) = control_flow_result {
-        let control_flow_label_opt = control_flow_instruction.get_label();
-        if control_flow_label_opt.is_none() {
-            return Ok(Instruction::ControlFlow(control_flow_instruction));
-        }
-        let control_flow_label = control_flow_label_opt.unwrap();
-        match labels.get(&control_flow_label.content) {
-            None => {
-                return Err(InstructionParseError::UnknownLabel(
-                    control_flow_label.content.clone(),
-                    inp.to_string(),
-                ));
-            }
-            Some(d) => {
-                control_flow_instruction.hydrate(d.clone());
-                return Ok(Instruction::ControlFlow(control_flow_instruction));
-            }
-        }
+    if let Ok(control_flow_instruction) = control_flow_result {
+        return resolve_label(Instruction::ControlFlow(control_flow_instruction), labels);
     }
-
     let control_flow_error = control_flow_result.unwrap_err();
     if !control_flow_error.is_unknown_instruction() {
         return Err(control_flow_error);
     }
-    let memory_result = Memory::from_str(inp).map(Instruction::Memory);
-    if memory_result.is_ok() {
-        return memory_result;
+    let memory_result = Memory::from_str(inp);
+    if let Ok(memory_instruction) = memory_result {
+        return resolve_label(Instruction::Memory(memory_instruction), labels);
     }
     let memory_error = memory_result.unwrap_err();
     if !memory_error.is_unknown_instruction() {
         return Err(memory_error);
     }
-    Misc::from_str(inp).map(Instruction::Misc)
+    Misc::from_str(inp)
+        .map(Instruction::Misc)
+        .map_err(|e| match e {
+            InstructionParseError::UnknownInstruction(_) => operand_count_error(inp).unwrap_or(e),
+            e => e,
+        })
 }
 
-pub fn parse(
-    input: &str,
-) -> Result<(Vec<PureElement>, HashMap<String, Rc<LocAwLabel>>), ParseError> {
+/// Returns an error, if `inp` starts with a known mnemonic, but has the wrong number of operands
+fn operand_count_error(inp: &str) -> Option<InstructionParseError> {
+    let parts = split_operands(inp, char::is_whitespace);
+    let expected = operand_count(parts[0])?;
+    (expected != parts.len() - 1).then(|| {
+        InstructionParseError::OperandCount(parts[0].to_string(), expected, parts.len() - 1)
+    })
+}
+
+/// Links the label `instruction` refers to, if any, to its definition in `labels`
+fn resolve_label(
+    mut instruction: Instruction,
+    labels: &LabelScope,
+) -> Result<Instruction, InstructionParseError> {
+    let label_ref = match instruction.get_label() {
+        Some(d) => d,
+        None => return Ok(instruction),
+    };
+    match labels.resolve(label_ref) {
+        None if labels.is_extern(label_ref) => Ok(instruction),
+        None => Err(InstructionParseError::UnknownLabel(
+            label_ref.content.clone(),
+            labels.suggest(label_ref),
+        )),
+        Some(d) => {
+            instruction.hydrate(d.clone());
+            Ok(instruction)
+        }
+    }
+}
+
+fn parse_directive(
+    inp: &str,
+    section: Section,
+    labels: &Labels,
+    data_size: u64,
+) -> Result<Directive, DirectiveParseError> {
+    let directive = Directive::from_str(inp)?;
+    let size = data_size + directive.size_at(data_size);
+    match &directive {
+        d if d.is_data() && section != Section::Data => {
+            Err(DirectiveParseError::OutsideDataSection(inp.to_string()))
+        }
+        Directive::Global(name) if !labels.map.contains_key(name) => {
+            Err(DirectiveParseError::UndefinedGlobal(name.clone()))
+        }
+        Directive::Extern(name) if labels.map.contains_key(name) => {
+            Err(DirectiveParseError::DefinedExtern(name.clone()))
+        }
+        _ if size > MAX_DATA_SIZE => Err(DirectiveParseError::DataTooLarge(size)),
+        _ => Ok(directive),
+    }
+}
+
+/// A parsed source together with every error found in it, see [`parse_all`]
+#[derive(Debug)]
+pub struct ParseOutput {
+    /// The elements of all lines without errors
+    pub elements: Vec<PureElement>,
+    pub labels: LabelMap,
+    /// The errors in the order of their lines, at most one per line
+    pub errors: Vec<ParseError>,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Parses `input`, failing with the first error, see [`parse_all`] to get every error
+pub fn parse(input: &str) -> Result<(Vec<PureElement>, LabelMap), ParseError> {
+    let output = parse_all(input);
+    match output.errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok((output.elements, output.labels)),
+    }
+}
+
+/// Parses `input` line by line, skipping the lines with errors instead of stopping at the first one.
+///
+/// The label [`DEFAULT_ENTRY`] isn't reported as unused, see [`parse_all_with_entry`].
+pub fn parse_all(input: &str) -> ParseOutput {
+    parse_all_with_entry(input, DEFAULT_ENTRY)
+}
+
+/// Parses `input` like [`parse_all`], the label `entry` execution starts at isn't reported as unused
+pub fn parse_all_with_entry(input: &str, entry: &str) -> ParseOutput {
     let lines = preprocess_input(input);
-    let (labels, labels_locs) = collect_labels(&lines);
+    let labels = collect_labels(&lines);
+    let labels_locs = &labels.locs;
     let mut ret = Vec::with_capacity(lines.len());
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let mut section = Section::Text;
+    // the alignment of the data section is unknown here, but padding is bounded by MAX_ALIGNMENT
+    let mut data_size = 0;
+    let mut global = None;
 
-    for (clean_index, (input_index, input_line)) in lines.iter().enumerate() {
-        if !labels_locs.contains_key(&clean_index) {
-            match parse_instruction(input_line, &labels) {
+    for (clean_index, line) in lines.iter().enumerate() {
+        let input_line = line.content;
+        let span = line.span(input_line);
+        if input_line.starts_with('.') && !labels_locs.contains_key(&clean_index) {
+            let directive = match parse_directive(input_line, section, &labels, data_size) {
+                Ok(d) => d,
+                Err(e) => {
+                    let span = match &e {
+                        DirectiveParseError::UnknownDirective(name) => {
+                            line.span(&input_line[..name.len()])
+                        }
+                        _ => span,
+                    };
+                    errors.push(ParseError::DirectiveParseError(
+                        e,
+                        span,
+                        input_line.to_string(),
+                    ));
+                    continue;
+                }
+            };
+            if let Directive::Section(d) = directive {
+                section = d;
+            }
+            data_size += directive.size_at(data_size);
+            ret.push(PureElement::Directive(directive, span));
+        } else if !labels_locs.contains_key(&clean_index) {
+            let parts = split_operands(input_line, char::is_whitespace);
+            let spans = InstructionSpans {
+                instruction: span,
+                mnemonic: line.span(parts[0]),
+                operands: parts[1..].iter().map(|p| line.span(p)).collect(),
+            };
+            let instruction = match section {
+                Section::Text => {
+                    let scope = LabelScope {
+                        labels: &labels,
+                        global,
+                        index: clean_index,
+                    };
+                    parse_instruction(input_line, &scope)
+                }
+                Section::Data => Err(InstructionParseError::OutsideTextSection(
+                    input_line.to_string(),
+                )),
+            };
+            match instruction {
                 Ok(d) => {
-                    ret.push(PureElement::Instruction(d));
+                    if let Some(warning) = case_mismatch(&d, &labels, &spans) {
+                        warnings.push(warning);
+                    }
+                    ret.push(PureElement::Instruction(d, spans));
                 }
                 Err(e) => {
-                    return Err(ParseError::InstructionParseError(
+                    let span = if e.is_unknown_instruction() {
+                        spans.mnemonic
+                    } else {
+                        e.operand()
+                            .and_then(|o| parts[1..].iter().position(|p| *p == o))
+                            .map_or(span, |i| spans.operands[i])
+                    };
+                    errors.push(ParseError::InstructionParseError(
                         e,
-                        *input_index,
+                        span,
                         input_line.to_string(),
                     ));
                 }
             }
         } else {
             let associated_name = labels_locs.get(&clean_index).unwrap();
-            let associated_label = labels.get(associated_name).cloned().unwrap();
-            ret.push(PureElement::Label(associated_label));
+            let associated_label = labels.map.get(associated_name).cloned().unwrap();
+            if !input_line.starts_with(|c: char| c == '.' || c.is_ascii_digit()) {
+                global = Some(associated_name.as_str());
+            }
+            if associated_label.loc != clean_index {
+                let first = &lines[associated_label.loc];
+                errors.push(ParseError::DuplicateLabel(
+                    source_name(associated_name).to_string(),
+                    span,
+                    first.span(first.content),
+                ));
+                continue;
+            }
+            ret.push(PureElement::Label(associated_label, span));
         }
     }
 
-    Ok((ret, labels))
+    warnings.extend(unused_labels(&ret, entry));
+    warnings.sort_by_key(|w| w.span());
+    ParseOutput {
+        elements: ret,
+        labels: labels.map,
+        errors,
+        warnings,
+    }
+}
+
+/// Warns about a reference of `instruction`, if another label only differs in case from it
+fn case_mismatch(
+    instruction: &Instruction,
+    labels: &Labels,
+    spans: &InstructionSpans,
+) -> Option<ParseWarning> {
+    let label_ref = instruction.get_label()?;
+    let name = &label_ref.label.as_ref()?.name;
+    let other = labels
+        .map
+        .keys()
+        .filter(|other| *other != name && other.eq_ignore_ascii_case(name))
+        .min()?;
+    Some(ParseWarning::CaseMismatch(
+        label_ref.content.clone(),
+        source_name(other).to_string(),
+        *spans.operands.last()?,
+    ))
+}
+
+/// Returns the labels of `elements`, that are neither referenced, declared `.global` nor `entry`
+fn unused_labels<'a>(
+    elements: &'a [PureElement],
+    entry: &'a str,
+) -> impl Iterator<Item = ParseWarning> + 'a {
+    let mut used = HashSet::from([entry]);
+    for element in elements {
+        match element {
+            PureElement::Instruction(i, _) => {
+                if let Some(label) = i.get_label().and_then(|l| l.label.as_ref()) {
+                    used.insert(label.name.as_str());
+                }
+            }
+            PureElement::Directive(Directive::Global(name), _) => {
+                used.insert(name.as_str());
+            }
+            _ => {}
+        }
+    }
+    elements.iter().filter_map(move |e| match e {
+        PureElement::Label(label, span) if !used.contains(label.name.as_str()) => Some(
+            ParseWarning::UnusedLabel(source_name(&label.name).to_string(), *span),
+        ),
+        _ => None,
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::parse;
+    use crate::{
+        instruction::MNEMONICS, parse, parse_all, parse_all_with_entry, parse_instruction,
+        span::Span, DirectiveParseError, InstructionParseError, LabelScope, Labels, ParseError,
+        ParseWarning, PureElement,
+    };
 
     #[test]
     fn it_works() {
@@ -178,4 +554,276 @@ mod tests {
         let (stream, _labels) = parse(DEMO_FILE).unwrap();
         println!("{:#?}", stream);
     }
+
+    #[test]
+    fn local_labels() {
+        let program = "
+        _start:
+            jmp :.loop
+        .loop:
+            jmp :1f
+        1:
+            jmp :1b
+        1:
+            jmp :1b
+        print.char:
+            jmp :.loop
+        .loop:
+            jmp :_start
+        ";
+        let (stream, labels) = parse(program).unwrap();
+
+        let targets: Vec<_> = stream
+            .iter()
+            .filter_map(|e| match e {
+                PureElement::Instruction(i, _) => Some(i.get_label().unwrap().label.clone()),
+                _ => None,
+            })
+            .map(|l| l.unwrap().name.clone())
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                "_start@.loop",
+                "1@4",
+                "1@4",
+                "1@6",
+                "print.char@.loop",
+                "_start"
+            ]
+        );
+        assert_eq!(labels.len(), 6);
+        assert!(matches!(
+            parse("main:\n\tjmp :1b\n1:\n"),
+            Err(ParseError::InstructionParseError(
+                InstructionParseError::UnknownLabel(..),
+                ..
+            ))
+        ));
+    }
+
+    #[test]
+    fn dotted_global_labels() {
+        let program = "main:\n\tjmp :.char\n\tjmp :main.char\n.char:\n\tnop\nmain.char:\n\tnop\n";
+        let (stream, labels) = parse(program).unwrap();
+
+        let targets: Vec<_> = stream
+            .iter()
+            .filter_map(|e| match e {
+                PureElement::Instruction(i, _) => i.get_label()?.label.clone(),
+                _ => None,
+            })
+            .map(|l| (l.name.clone(), l.loc))
+            .collect();
+        assert_eq!(
+            targets,
+            [("main@.char".to_string(), 3), ("main.char".to_string(), 5)]
+        );
+        assert_eq!(labels.len(), 3);
+    }
+
+    #[test]
+    fn global_and_extern() {
+        let (stream, _) =
+            parse(".global _start\n.extern print\n_start:\n\tcal :print\n\texit 0\n").unwrap();
+
+        let print = stream.iter().find_map(|e| match e {
+            PureElement::Instruction(i, _) => i.get_label(),
+            _ => None,
+        });
+        assert_eq!(print.map(|l| l.label.is_none()), Some(true));
+        assert!(matches!(
+            parse(".global main\n_start:\n\tnop\n"),
+            Err(ParseError::DirectiveParseError(
+                DirectiveParseError::UndefinedGlobal(_),
+                Span { line: 0, .. },
+                _
+            ))
+        ));
+        assert!(matches!(
+            parse(".extern main\nmain:\n\tnop\n"),
+            Err(ParseError::DirectiveParseError(
+                DirectiveParseError::DefinedExtern(_),
+                Span { line: 0, .. },
+                _
+            ))
+        ));
+        assert!(matches!(
+            parse(".extern print\nmain:\n\tjmp :.print\n"),
+            Err(ParseError::InstructionParseError(
+                InstructionParseError::UnknownLabel(..),
+                ..
+            ))
+        ));
+    }
+
+    #[test]
+    fn spans() {
+        let source = "main:\n    jmpeq $G_0 'a' :main # comment\n.data\n\t.byte 1\n";
+        let (stream, _) = parse(source).unwrap();
+        let text = |span: &Span| &source[span.start..span.end];
+
+        let PureElement::Instruction(_, spans) = &stream[1] else {
+            panic!("{:?} isn't an instruction", stream[1])
+        };
+        assert_eq!(text(&spans.instruction), "jmpeq $G_0 'a' :main");
+        assert_eq!(text(&spans.mnemonic), "jmpeq");
+        let operands: Vec<_> = spans.operands.iter().map(text).collect();
+        assert_eq!(operands, ["$G_0", "'a'", ":main"]);
+        assert_eq!((spans.operands[2].line, spans.operands[2].column), (1, 19));
+        assert!(matches!(&stream[0], PureElement::Label(_, span) if text(span) == "main:"));
+        assert!(matches!(&stream[3], PureElement::Directive(_, span) if span.to_string() == "4:2"));
+    }
+
+    #[test]
+    fn error_spans() {
+        let span = |source: &str| parse(source).unwrap_err().span().to_string();
+
+        assert_eq!(span("main:\n  addts $G_0 $G_9 1\n"), "2:14");
+        assert_eq!(span("main:\n  jmp :missing\n"), "2:7");
+        assert_eq!(span("main:\n  ad $G_0 1\n"), "2:3");
+        assert_eq!(span("main:\n  nop 1\n"), "2:3");
+        assert_eq!(span("\n\n  .byte 1\n"), "3:3");
+    }
+
+    #[test]
+    fn recovers_from_errors() {
+        let output = parse_all(
+            "main:\n  noi $G_0\n  mov $G_9 1\n  nop\n  jmp :mian\n  addts $G_0 1\n  pusj $G_0\n  .byte 1\n  exit 'ab'\n",
+        );
+
+        let lines: Vec<_> = output.errors.iter().map(|e| e.span().line).collect();
+        assert_eq!(lines, [2, 4, 5, 6, 7, 8]);
+        assert!(matches!(
+            output.errors[2],
+            ParseError::InstructionParseError(InstructionParseError::OperandCount(_, 3, 2), ..)
+        ));
+        assert!(matches!(
+            output.errors[3],
+            ParseError::InstructionParseError(InstructionParseError::UnknownInstruction(_), ..)
+        ));
+        assert_eq!(output.elements.len(), 3);
+        assert_eq!(output.labels.len(), 1);
+    }
+
+    #[test]
+    fn duplicate_labels() {
+        let output = parse_all("main:\n\tjmp :.loop\n.loop:\n\tnop\nmain:\n.loop:\n1:\n1:\n");
+
+        let errors: Vec<_> = output
+            .errors
+            .iter()
+            .map(|e| match e {
+                ParseError::DuplicateLabel(name, span, first) => {
+                    (name.as_str(), span.line, first.line)
+                }
+                e => panic!("unexpected error {:?}", e),
+            })
+            .collect();
+        assert_eq!(errors, [("main", 4, 0), (".loop", 5, 2)]);
+        let loop_ref = output.elements.iter().find_map(|e| match e {
+            PureElement::Instruction(i, _) => i.get_label()?.label.clone(),
+            _ => None,
+        });
+        assert_eq!(loop_ref.map(|l| l.loc), Some(2));
+    }
+
+    #[test]
+    fn label_warnings() {
+        let output = parse_all(
+            ".global lib\nlib:\n\tcal :Helper\n\tret $Z\nHelper:\nhelper:\n\tret $Z\nunused:\n1:\n\
+            _start:\n",
+        );
+
+        let warnings: Vec<_> = output
+            .warnings
+            .iter()
+            .map(|w| (w.span().line, w.to_string()))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                (
+                    2,
+                    "label `Helper` only differs in case from the label `helper`"
+                ),
+                (5, "label `helper` is never used"),
+                (7, "label `unused` is never used"),
+                (8, "label `1` is never used"),
+            ]
+            .map(|(line, w)| (line, w.to_string()))
+        );
+        assert!(matches!(
+            output.warnings[0],
+            ParseWarning::CaseMismatch(
+                ..,
+                Span {
+                    start: 22,
+                    end: 29,
+                    ..
+                }
+            )
+        ));
+        let warnings = parse_all_with_entry("_start:\nmain:\n", "main").warnings;
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].to_string(), "label `_start` is never used");
+    }
+
+    #[test]
+    fn mnemonics_are_known() {
+        for (mnemonic, count) in MNEMONICS {
+            let instruction = |count| {
+                let operands = vec!["$G_0"; count];
+                format!("{} {}", mnemonic, operands.join(" "))
+            };
+
+            let scope = LabelScope {
+                labels: &Labels::default(),
+                global: None,
+                index: 0,
+            };
+            let error = parse_instruction(instruction(*count).trim(), &scope).err();
+            assert!(
+                !error.is_some_and(|e| e.is_unknown_instruction()),
+                "{}",
+                mnemonic
+            );
+            assert!(matches!(
+                parse_instruction(&instruction(count + 1), &scope),
+                Err(InstructionParseError::OperandCount(..))
+            ));
+        }
+    }
+
+    #[test]
+    fn sections() {
+        let (stream, _) =
+            parse("main:\n\tmov $G_0 :msg\n.data\nmsg:\n\t.asciz \"# not a comment\"\n").unwrap();
+
+        assert_eq!(stream.len(), 5);
+        assert!(matches!(
+            parse(".data\n\tnop\n"),
+            Err(ParseError::InstructionParseError(
+                InstructionParseError::OutsideTextSection(_),
+                Span { line: 1, .. },
+                _
+            ))
+        ));
+        assert!(matches!(
+            parse("\t.word 1\n"),
+            Err(ParseError::DirectiveParseError(
+                DirectiveParseError::OutsideDataSection(_),
+                Span { line: 0, .. },
+                _
+            ))
+        ));
+        assert!(matches!(
+            parse(".data\n\t.zero 0x1000000\n\t.zero 1\n"),
+            Err(ParseError::DirectiveParseError(
+                DirectiveParseError::DataTooLarge(0x1000001),
+                Span { line: 2, .. },
+                _
+            ))
+        ));
+    }
 }
@@ -1,15 +1,7 @@
-use std::{num::IntErrorKind, str::FromStr};
+use std::{fmt::Display, num::IntErrorKind, str::FromStr};
 
-use lazy_static::lazy_static;
-use regex::Regex;
 use thiserror::Error;
 
-const LITERAL_EXP: &str = "(-?)([0-9]+)(u|s)?";
-
-lazy_static! {
-    static ref LITERAL_RE: Regex = Regex::new(LITERAL_EXP).unwrap();
-}
-
 #[derive(Copy, Clone, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LiteralToken {
     Signed(i64),
@@ -26,80 +18,336 @@ pub enum LiteralParseError {
     SignedLiteralToBig(String, i64),
     #[error("unsigend literal `{0}` exceeds u64 bounds `{1}`")]
     UnsignedLiteralToBig(String, u64),
+    #[error("character literal `{0}` is invalid formatted")]
+    InvalidCharacter(String),
+    #[error("string literal `{0}` is invalid formatted")]
+    InvalidString(String),
+}
+
+/// Splits a literal into its sign, radix, digits and type suffix.
+///
+/// Numbers are decimal or prefixed with `0x` (hex), `0o` (octal) or `0b` (binary). Digits can be
+/// separated by underscores, the optional suffix `u` or `s` determines the type of the literal.
+fn split_number(s: &str) -> Option<(bool, u32, &str, Option<char>)> {
+    let (s, suffix) = match s.strip_suffix('u') {
+        Some(d) => (d, Some('u')),
+        None => match s.strip_suffix('s') {
+            Some(d) => (d, Some('s')),
+            None => (s, None),
+        },
+    };
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(d) => (true, d),
+        None => (false, s),
+    };
+    let (radix, digits) = match s.get(..2) {
+        Some("0x") => (16, &s[2..]),
+        Some("0o") => (8, &s[2..]),
+        Some("0b") => (2, &s[2..]),
+        _ => (10, s),
+    };
+
+    let valid = digits.starts_with(|c: char| c.is_digit(radix))
+        && digits.chars().all(|c| c == '_' || c.is_digit(radix));
+    valid.then_some((negative, radix, digits, suffix))
 }
 
 impl FromStr for LiteralToken {
     type Err = LiteralParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let cap = match LITERAL_RE.captures(s) {
-            None => return Err(LiteralParseError::InvalidFormatted(s.to_string())),
-            Some(d) => d,
-        };
+        if s.starts_with('\'') {
+            return parse_char_literal(s);
+        }
+        let (negative, radix, digits, suffix) =
+            split_number(s).ok_or_else(|| LiteralParseError::InvalidFormatted(s.to_string()))?;
 
-        let negative = matches!(cap.get(1).unwrap().as_str(), "-");
-        let number_str = cap.get(2).unwrap().as_str();
-        match cap.get(3) {
-            Some(d) => match d.as_str() {
-                "s" => parse_signed_literal(s, negative, number_str),
-                "u" => {
-                    if negative {
-                        return Err(LiteralParseError::ConflictingSignAndLiteralType(
+        let digits = digits.replace('_', "");
+        let magnitude = match u64::from_str_radix(&digits, radix) {
+            Ok(d) => d,
+            Err(e) => match e.kind() {
+                IntErrorKind::PosOverflow => match suffix {
+                    Some('u') if !negative => {
+                        return Err(LiteralParseError::UnsignedLiteralToBig(
                             s.to_string(),
-                            'u',
-                        ));
+                            u64::MAX,
+                        ))
                     }
-                    let number = match u64::from_str(number_str) {
-                        Ok(d) => d,
-                        Err(e) => match e.kind() {
-                            IntErrorKind::PosOverflow => {
-                                return Err(LiteralParseError::UnsignedLiteralToBig(
-                                    s.to_string(),
-                                    u64::MAX,
-                                ))
-                            }
-                            IntErrorKind::NegOverflow => {
-                                return Err(LiteralParseError::UnsignedLiteralToBig(
-                                    s.to_string(),
-                                    u64::MIN,
-                                ))
-                            }
-                            _ => panic!("this shouldn't have happened"),
-                        },
-                    };
-                    Ok(LiteralToken::Unsigned(number))
-                }
-                _ => panic!("this shouldn't be here"),
+                    _ => return Err(signed_to_big(s, negative)),
+                },
+                _ => panic!("this shouldn't have happened"),
             },
-            None => parse_signed_literal(s, negative, number_str),
+        };
+
+        match suffix {
+            Some('u') if negative => Err(LiteralParseError::ConflictingSignAndLiteralType(
+                s.to_string(),
+                'u',
+            )),
+            Some('u') => Ok(LiteralToken::Unsigned(magnitude)),
+            _ if negative => 0i64
+                .checked_sub_unsigned(magnitude)
+                .map(LiteralToken::Signed)
+                .ok_or_else(|| signed_to_big(s, negative)),
+            _ => i64::try_from(magnitude)
+                .map(LiteralToken::Signed)
+                .map_err(|_| signed_to_big(s, negative)),
         }
     }
 }
 
-fn parse_signed_literal(
-    inp: &str,
-    negative: bool,
-    number_str: &str,
-) -> Result<LiteralToken, LiteralParseError> {
-    let mut number = match i64::from_str(number_str) {
-        Ok(d) => d,
-        Err(e) => match e.kind() {
-            IntErrorKind::PosOverflow => {
-                return Err(LiteralParseError::SignedLiteralToBig(
-                    inp.to_string(),
-                    i64::MAX,
-                ))
-            }
-            IntErrorKind::NegOverflow => {
-                return Err(LiteralParseError::SignedLiteralToBig(
-                    inp.to_string(),
-                    i64::MIN,
-                ))
+impl Display for LiteralToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LiteralToken::Signed(value) => write!(f, "{}", value),
+            LiteralToken::Unsigned(value) => write!(f, "{}u", value),
+        }
+    }
+}
+
+fn signed_to_big(inp: &str, negative: bool) -> LiteralParseError {
+    let bound = if negative { i64::MIN } else { i64::MAX };
+    LiteralParseError::SignedLiteralToBig(inp.to_string(), bound)
+}
+
+/// Parses a character literal like `'a'` or `'\n'`, optionally followed by a `u` or `s` suffix,
+/// into the value of its code point
+fn parse_char_literal(s: &str) -> Result<LiteralToken, LiteralParseError> {
+    let invalid = || LiteralParseError::InvalidCharacter(s.to_string());
+    let (inner, suffix) = match s.rfind('\'') {
+        Some(end) if end > 0 => (&s[1..end], &s[end + 1..]),
+        _ => return Err(invalid()),
+    };
+
+    let mut chars = inner.chars();
+    let value = match chars.next() {
+        Some('\\') => parse_escape(&mut chars, '\'').ok_or_else(invalid)?,
+        Some('\'') | None => return Err(invalid()),
+        Some(c) => c as u32,
+    };
+    if chars.next().is_some() {
+        return Err(invalid());
+    }
+
+    match suffix {
+        "" | "s" => Ok(LiteralToken::Signed(value.into())),
+        "u" => Ok(LiteralToken::Unsigned(value.into())),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parses the escape sequence after a `\`, returning the value of the escaped character.
+///
+/// Supported are `\n`, `\t`, `\r`, `\0`, `\\`, `\xHH` and an escaped `quote`.
+fn parse_escape(chars: &mut std::str::Chars, quote: char) -> Option<u32> {
+    let value = match chars.next()? {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        '0' => '\0',
+        '\\' => '\\',
+        'x' => {
+            let digits = chars.as_str().get(..2)?;
+            if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
             }
-            _ => panic!("this shouldn't have happened"),
-        },
+            let value = u8::from_str_radix(digits, 16).ok()?;
+            chars.nth(1);
+            return Some(value.into());
+        }
+        c if c == quote => quote,
+        _ => return None,
     };
-    if negative {
-        number *= -1;
+    Some(value as u32)
+}
+
+/// Parses a double quoted string literal into its bytes, using the escapes of character
+/// literals, `\xHH` produces a single byte
+pub fn parse_string_literal(s: &str) -> Result<Vec<u8>, LiteralParseError> {
+    let invalid = || LiteralParseError::InvalidString(s.to_string());
+    let inner = s
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+
+    let mut ret = Vec::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Err(invalid()),
+            '\\' if chars.as_str().starts_with('x') => {
+                let value = parse_escape(&mut chars, '"').ok_or_else(invalid)?;
+                ret.push(value as u8);
+            }
+            '\\' => {
+                let value = parse_escape(&mut chars, '"').ok_or_else(invalid)?;
+                let c = char::from_u32(value).expect("escapes are valid characters");
+                ret.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+            c => ret.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    Ok(ret)
+}
+
+/// Renders `bytes` as a double quoted string literal, that [`parse_string_literal`] reads back
+pub fn escape_string_literal(bytes: &[u8]) -> String {
+    let mut ret = String::with_capacity(bytes.len() + 2);
+    ret.push('"');
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\n' => ret.push_str("\\n"),
+                '\t' => ret.push_str("\\t"),
+                '\r' => ret.push_str("\\r"),
+                '\0' => ret.push_str("\\0"),
+                '\\' => ret.push_str("\\\\"),
+                '"' => ret.push_str("\\\""),
+                c if c.is_ascii_control() => ret.push_str(&format!("\\x{:02x}", c as u32)),
+                c => ret.push(c),
+            }
+        }
+        for byte in chunk.invalid() {
+            ret.push_str(&format!("\\x{:02x}", byte));
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+/// Splits `inp` at every character matching `is_separator`, that isn't part of a character or
+/// string literal
+pub(crate) fn split_operands(inp: &str, is_separator: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut ret = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in inp.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if is_separator(c) => {
+                if let Some(start) = start.take() {
+                    ret.push(&inp[start..index]);
+                }
+            }
+            None => {
+                if c == '\'' || c == '"' {
+                    quote = Some(c);
+                }
+                start.get_or_insert(index);
+            }
+        }
+    }
+    if let Some(start) = start {
+        ret.push(&inp[start..]);
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> LiteralToken {
+        LiteralToken::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn radix_and_separators() {
+        assert_eq!(parse("0xFF"), LiteralToken::Signed(255));
+        assert_eq!(parse("-0x10"), LiteralToken::Signed(-16));
+        assert_eq!(parse("0b1010_1010u"), LiteralToken::Unsigned(0xaa));
+        assert_eq!(parse("0o777"), LiteralToken::Signed(511));
+        assert_eq!(parse("1_000_000"), LiteralToken::Signed(1_000_000));
+        assert_eq!(
+            parse("0xffff_ffff_ffff_ffffu"),
+            LiteralToken::Unsigned(u64::MAX)
+        );
+        assert_eq!(
+            parse("-9223372036854775808"),
+            LiteralToken::Signed(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        for s in ["", "_1", "0x", "0xg", "12abc", "1.5", "--1", "0b102"] {
+            assert!(
+                matches!(
+                    LiteralToken::from_str(s),
+                    Err(LiteralParseError::InvalidFormatted(_))
+                ),
+                "{}",
+                s
+            );
+        }
+        assert!(matches!(
+            LiteralToken::from_str("0x8000_0000_0000_0000"),
+            Err(LiteralParseError::SignedLiteralToBig(_, i64::MAX))
+        ));
+        assert!(matches!(
+            LiteralToken::from_str("0x1_0000_0000_0000_0000u"),
+            Err(LiteralParseError::UnsignedLiteralToBig(_, u64::MAX))
+        ));
+        assert!(matches!(
+            LiteralToken::from_str("-0x1u"),
+            Err(LiteralParseError::ConflictingSignAndLiteralType(_, 'u'))
+        ));
+    }
+
+    #[test]
+    fn characters() {
+        assert_eq!(parse("'A'"), LiteralToken::Signed(65));
+        assert_eq!(parse("' '"), LiteralToken::Signed(32));
+        assert_eq!(parse(r"'\n'"), LiteralToken::Signed(10));
+        assert_eq!(parse(r"'\''u"), LiteralToken::Unsigned(39));
+        assert_eq!(parse(r"'\x7f'"), LiteralToken::Signed(127));
+        assert_eq!(parse("'ä'"), LiteralToken::Signed(228));
+        for s in [
+            "''", "'ab'", "'a", r"'\q'", "'a'x", "'''", r"'\x+f'", r"'\x7'",
+        ] {
+            assert!(
+                matches!(
+                    LiteralToken::from_str(s),
+                    Err(LiteralParseError::InvalidCharacter(_))
+                ),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(
+            split_operands(r"mov  $G_0 ' ' '\''", char::is_whitespace),
+            ["mov", "$G_0", "' '", r"'\''"]
+        );
+        assert_eq!(
+            split_operands(r#".ascii "a, \"b\"", ',', 1"#, |c| c == ',' || c == ' '),
+            [".ascii", r#""a, \"b\"""#, "','", "1"]
+        );
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(
+            parse_string_literal(r#""a\"b\n\xff""#).unwrap(),
+            b"a\"b\n\xff"
+        );
+        assert!(parse_string_literal(r#""a"b""#).is_err());
+        assert!(parse_string_literal(r#""a\""#).is_err());
+        assert!(parse_string_literal("\"").is_err());
+        assert!(parse_string_literal(r#""\x+f""#).is_err());
+        assert!(parse_string_literal(r#""\x-1a""#).is_err());
+
+        for bytes in ["tab\t\"quoted\" \\ \x01ä\u{85}".as_bytes(), &[0xff, b'a']] {
+            assert_eq!(
+                parse_string_literal(&escape_string_literal(bytes)).unwrap(),
+                bytes
+            );
+        }
     }
-    Ok(LiteralToken::Signed(number))
 }
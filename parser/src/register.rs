@@ -1,6 +1,12 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 use thiserror::Error;
 
+/// The names of all registers
+pub const REGISTERS: &[&str] = &[
+    "$G_0", "$G_1", "$G_2", "$G_3", "$G_4", "$G_5", "$G_6", "$G_7", "$S_B", "$S_E", "$R", "$I",
+    "$Z", "$E",
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RegisterToken {
     GeneralPurpose(u8),
@@ -43,3 +49,17 @@ impl FromStr for RegisterToken {
         Ok(ret)
     }
 }
+
+impl Display for RegisterToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegisterToken::GeneralPurpose(i) => write!(f, "$G_{}", i),
+            RegisterToken::StackBase => write!(f, "$S_B"),
+            RegisterToken::StackEnd => write!(f, "$S_E"),
+            RegisterToken::Return => write!(f, "$R"),
+            RegisterToken::Instruction => write!(f, "$I"),
+            RegisterToken::Zero => write!(f, "$Z"),
+            RegisterToken::Error => write!(f, "$E"),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt::Display;
+
+use crate::{comment::strip_coment, literal::split_operands};
+
+/// A range of the source.
+///
+/// `start` and `end` are byte offsets into the whole source, `line` and `column` locate `start`,
+/// both starting at 0. The column counts characters, not bytes.
+#[derive(Debug, Clone, Copy, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    /// Returns the span of `part` in the source line `line` at index `index`, which starts at the
+    /// byte offset `offset` of the source. `part` has to be a slice of `line`.
+    pub(crate) fn in_line(index: usize, offset: usize, line: &str, part: &str) -> Span {
+        let start = part.as_ptr() as usize - line.as_ptr() as usize;
+        debug_assert!(start + part.len() <= line.len());
+        Span {
+            start: offset + start,
+            end: offset + start + part.len(),
+            line: index,
+            column: line[..start].chars().count(),
+        }
+    }
+
+    /// Returns the span from `column` of `line` in `source` to the end of the line without its
+    /// comment, like the rest of an instruction starting at an operand
+    pub fn at(source: &str, line: usize, column: usize) -> Option<Span> {
+        let offset: usize = source.split('\n').take(line).map(|l| l.len() + 1).sum();
+        let content = strip_coment(source.split('\n').nth(line)?).trim_end();
+        let start = content.char_indices().nth(column)?.0;
+        Some(Span {
+            start: offset + start,
+            end: offset + content.len(),
+            line,
+            column,
+        })
+    }
+
+    /// Returns the span of the operand starting at `column` of `line` in `source`
+    pub fn operand_at(source: &str, line: usize, column: usize) -> Option<Span> {
+        let span = Span::at(source, line, column)?;
+        let operand = split_operands(&source[span.start..span.end], char::is_whitespace)[0];
+        Some(Span {
+            end: span.start + operand.len(),
+            ..span
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Displays the line and column starting at 1, like `3:5`
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line + 1, self.column + 1)
+    }
+}
+
+/// The location of an instruction and its tokens in the source
+#[derive(Debug, Clone, Hash, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct InstructionSpans {
+    /// The whole instruction, without the comment
+    pub instruction: Span,
+    pub mnemonic: Span,
+    /// The registers, literals and label references in the order they are written
+    pub operands: Vec<Span>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn span_in_line() {
+        let source = "main:\n  jmp :ä_loop # x";
+        let line = &source[6..];
+
+        let span = Span::in_line(1, 6, line, &line[6..14]);
+
+        assert_eq!(&source[span.start..span.end], ":ä_loop");
+        assert_eq!((span.line, span.column, span.len()), (1, 6, 8));
+        assert_eq!(span.to_string(), "2:7");
+        assert_eq!(Span::at(source, 1, 6), Some(span));
+        assert_eq!(Span::at(source, 1, 16), None);
+        assert_eq!(Span::operand_at(source, 1, 6), Some(span));
+
+        let operand = Span::operand_at("\tmov $G_0 ' ' # x", 0, 10).unwrap();
+        assert_eq!((operand.start, operand.end), (10, 13));
+    }
+}
@@ -0,0 +1,64 @@
+/// Returns the candidate `word` is most likely a typo of.
+///
+/// Candidates only differing in case are preferred, otherwise the closest candidate is returned if
+/// it is at most a third of the length of `word` edits away, but at least one.
+pub fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = (word.chars().count() / 3).max(1);
+    let lowercase = word.to_lowercase();
+    candidates
+        .into_iter()
+        .filter(|c| *c != word)
+        .map(|c| {
+            let distance = if c.to_lowercase() == lowercase {
+                0
+            } else {
+                distance(word, c)
+            };
+            (distance, c)
+        })
+        .filter(|(distance, _)| *distance <= limit)
+        .min()
+        .map(|(_, c)| c)
+}
+
+/// The number of inserted, deleted, replaced or swapped adjacent characters to get from `a` to `b`
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // rows[i][j] is the distance between the first i characters of a and the first j of b
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    rows[0] = (0..=b.len()).collect();
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = (a[i - 1] != b[j - 1]) as usize;
+            let mut min = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                min = min.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = min;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggestions() {
+        let mnemonics = ["nop", "noti", "nott", "mov", "jmp"];
+
+        assert_eq!(closest("noit", mnemonics), Some("noti"));
+        assert_eq!(closest("mvo", mnemonics), Some("mov"));
+        assert_eq!(closest("JMP", mnemonics), Some("jmp"));
+        assert_eq!(closest("addts", mnemonics), None);
+        assert_eq!(closest("nop", mnemonics), None);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+}